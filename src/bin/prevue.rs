@@ -0,0 +1,361 @@
+// Command-line renderer, built with the `cli` feature
+
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::{env, fs};
+
+use anyhow::Context;
+use prevue::{DataFormat, Loader, Options, Site, TemplateError, Whitespace};
+use serde_json::Value;
+
+const USAGE: &str = "\
+Usage: prevue [OPTIONS] <TEMPLATE>
+       prevue build [OPTIONS] <SITE> -o <DIR>
+
+Render a Vue-syntax HTML template. Includes are found next to the template.
+`build` renders the pages of a site directory that changed since the last build.
+`--data`, `--format` and `--strict` are for templates, `--layout` for `build`.
+
+Options:
+  -d, --data <FILE>        Data file, in JSON, YAML or TOML; `-` reads standard input
+  -f, --format <FORMAT>    Format of the data: json, yaml or toml
+                           [default: from the file extension, json for standard input]
+  -o, --output <FILE>      Write to a file instead of standard output
+  -w, --whitespace <MODE>  Whitespace of the output: preserve, condense, minify or pretty
+                           [default: preserve]
+      --fragment           Write the contents of <body> alone
+      --strict             Fail on warnings
+      --sandbox            Evaluate expressions in a hardened realm
+      --layout <FILE>      Layout of the site pages without one of their own
+  -h, --help               Print this help
+  -V, --version            Print the version
+";
+
+struct Args {
+    // Template, or site directory for `build`
+    template: PathBuf,
+    build: bool,
+    data: Option<String>,
+    format: Option<DataFormat>,
+    output: Option<PathBuf>,
+    whitespace: Whitespace,
+    fragment: bool,
+    strict: bool,
+    sandbox: bool,
+    layout: Option<String>,
+}
+
+enum Command {
+    Render(Args),
+    Help,
+    Version,
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(Command::Render(args)) => args,
+        Ok(Command::Help) => {
+            print!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::Version) => {
+            println!("prevue {}", env!("CARGO_PKG_VERSION"));
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let report = Report {
+        color: io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none(),
+    };
+    match run(&args, &report) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            report.error(&args, &err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_args(argv: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut argv = argv.peekable();
+    let build = argv.next_if(|arg| arg == "build").is_some();
+    let mut template = None;
+    let mut data = None;
+    let mut format = None;
+    let mut output = None;
+    let mut whitespace = Whitespace::Preserve;
+    let mut fragment = false;
+    let mut strict = false;
+    let mut sandbox = false;
+    let mut layout = None;
+
+    while let Some(arg) = argv.next() {
+        // `--name=value` and `--name value` are the same
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline
+                .map(str::to_string)
+                .or_else(|| argv.next())
+                .ok_or_else(|| format!("`{name}` needs a value"))
+        };
+
+        match name.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "-d" | "--data" => data = Some(value()?),
+            "-f" | "--format" => {
+                let name = value()?;
+                format = Some(
+                    DataFormat::from_name(&name)
+                        .ok_or_else(|| format!("unknown data format `{name}`"))?,
+                );
+            }
+            "-o" | "--output" => output = Some(PathBuf::from(value()?)),
+            "-w" | "--whitespace" => {
+                whitespace = match value()?.as_str() {
+                    "preserve" => Whitespace::Preserve,
+                    "condense" => Whitespace::Condense,
+                    "minify" => Whitespace::Minify,
+                    "pretty" => Whitespace::Pretty,
+                    mode => return Err(format!("unknown whitespace mode `{mode}`")),
+                };
+            }
+            "--fragment" => fragment = true,
+            "--strict" => strict = true,
+            "--sandbox" => sandbox = true,
+            "--layout" => layout = Some(value()?),
+            _ if arg.starts_with('-') && arg != "-" => {
+                return Err(format!("unknown option `{arg}`"));
+            }
+            _ if template.is_none() => template = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+
+    let template = template.ok_or(if build {
+        "no site directory given"
+    } else {
+        "no template given"
+    })?;
+    if build && output.is_none() {
+        return Err("`build` needs an output directory (`-o`)".to_string());
+    }
+    // Sites read their own data files, and warnings of site pages are not collected
+    let unused = if build {
+        [
+            ("--data", data.is_some()),
+            ("--format", format.is_some()),
+            ("--strict", strict),
+        ]
+        .into_iter()
+        .find_map(|(option, set)| set.then_some(option))
+    } else {
+        layout.is_some().then_some("--layout")
+    };
+    if let Some(option) = unused {
+        let mode = if build { "with" } else { "without" };
+        return Err(format!("`{option}` can't be used {mode} `build`"));
+    }
+    Ok(Command::Render(Args {
+        template,
+        build,
+        data,
+        format,
+        output,
+        whitespace,
+        fragment,
+        strict,
+        sandbox,
+        layout,
+    }))
+}
+
+fn run(args: &Args, report: &Report) -> Result<(), anyhow::Error> {
+    if args.build {
+        return build(args);
+    }
+
+    let source = fs::read_to_string(&args.template)
+        .with_context(|| format!("can't read `{}`", args.template.display()))?;
+    let data = read_data(args)?;
+
+    let options = Options::new()
+        .loader(Loader::new().dir(template_dir(&args.template)))
+        .whitespace(args.whitespace)
+        .fragment(args.fragment)
+        .sandbox(args.sandbox);
+    let (output, warnings) = options.render_with_warnings(source, data)?;
+
+    for warning in &warnings {
+        let error = TemplateError {
+            file: warning.file.clone(),
+            line: warning.line,
+            message: warning.message.clone(),
+        };
+        report.diagnostic(args, if args.strict { "error" } else { "warning" }, &error);
+    }
+    if args.strict && !warnings.is_empty() {
+        anyhow::bail!(
+            "{} warning{} in strict mode",
+            warnings.len(),
+            if warnings.len() == 1 { "" } else { "s" }
+        );
+    }
+
+    match &args.output {
+        Some(path) => {
+            fs::write(path, output).with_context(|| format!("can't write `{}`", path.display()))?
+        }
+        None => io::stdout().lock().write_all(output.as_bytes())?,
+    }
+    Ok(())
+}
+
+// Data of the render: a file, standard input or nothing
+fn read_data(args: &Args) -> Result<Value, anyhow::Error> {
+    let Some(data) = &args.data else {
+        return Ok(Value::Object(Default::default()));
+    };
+
+    let (source, format, name) = if data == "-" {
+        let mut source = String::new();
+        io::stdin().read_to_string(&mut source)?;
+        (
+            source,
+            args.format.unwrap_or(DataFormat::Json),
+            "standard input",
+        )
+    } else {
+        let path = Path::new(data);
+        let format = args
+            .format
+            .or_else(|| DataFormat::from_path(path))
+            .with_context(|| {
+                format!("can't tell the format of `{data}` from its extension; use `--format`")
+            })?;
+        let source = fs::read_to_string(path).with_context(|| format!("can't read `{data}`"))?;
+        (source, format, data.as_str())
+    };
+
+    format
+        .parse(&source)
+        .with_context(|| format!("invalid data in {name}"))
+}
+
+// Build a site directory, printing what changed
+fn build(args: &Args) -> Result<(), anyhow::Error> {
+    let Some(out) = &args.output else {
+        anyhow::bail!("`build` needs an output directory");
+    };
+    let mut site = Site::new(&args.template, out).options(
+        Options::new()
+            .whitespace(args.whitespace)
+            .fragment(args.fragment)
+            .sandbox(args.sandbox),
+    );
+    if let Some(layout) = &args.layout {
+        site = site.layout(layout);
+    }
+    let build = site.build()?;
+
+    for path in build.rendered.iter().chain(&build.copied) {
+        eprintln!("wrote {}", out.join(path).display());
+    }
+    for path in &build.removed {
+        eprintln!("removed {}", out.join(path).display());
+    }
+    eprintln!(
+        "{} rendered, {} copied, {} unchanged, {} removed",
+        build.rendered.len(),
+        build.copied.len(),
+        build.unchanged.len(),
+        build.removed.len()
+    );
+    Ok(())
+}
+
+fn template_dir(template: &Path) -> PathBuf {
+    match template.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+// Errors and warnings on standard error, with the template line they point at
+struct Report {
+    color: bool,
+}
+
+impl Report {
+    fn error(&self, args: &Args, err: &anyhow::Error) {
+        match err.downcast_ref::<TemplateError>() {
+            Some(error) => self.diagnostic(args, "error", error),
+            None => eprintln!("{}: {err:#}", self.paint("1;31", "error")),
+        }
+    }
+
+    fn diagnostic(&self, args: &Args, level: &str, error: &TemplateError) {
+        let color = if level == "error" { "1;31" } else { "1;33" };
+        eprintln!(
+            "{}: {}",
+            self.paint(color, level),
+            self.paint("1", &error.message)
+        );
+
+        // Errors of included templates name them relative to the template directory
+        let (path, source) = match &error.file {
+            Some(file) => {
+                let path = template_dir(&args.template).join(file);
+                let source = fs::read_to_string(&path).ok();
+                (path, source)
+            }
+            None => (
+                args.template.clone(),
+                fs::read_to_string(&args.template).ok(),
+            ),
+        };
+        let Some(line) = error.line else {
+            eprintln!("  {} {}", self.paint("1;34", "-->"), path.display());
+            return;
+        };
+        let gutter = " ".repeat(line.to_string().len());
+        eprintln!(
+            "{gutter}{} {}:{line}",
+            self.paint("1;34", "-->"),
+            path.display()
+        );
+
+        let Some(text) = source
+            .as_deref()
+            .and_then(|source| source.lines().nth(line - 1))
+        else {
+            return;
+        };
+        let text = text.trim_end();
+        let indent = text.len() - text.trim_start().len();
+        let bar = self.paint("1;34", "|");
+        eprintln!("{gutter} {bar}");
+        eprintln!("{} {bar} {text}", self.paint("1;34", &line.to_string()));
+        eprintln!(
+            "{gutter} {bar} {}{}",
+            &text[..indent],
+            self.paint(color, &"^".repeat(text[indent..].chars().count().max(1)))
+        );
+    }
+
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.color {
+            format!("\x1b[{code}m{text}\x1b[0m")
+        } else {
+            text.to_string()
+        }
+    }
+}
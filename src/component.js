@@ -0,0 +1,58 @@
+// Install a component definition on the root scope, in Vue's order: props defaults, methods,
+// `data()` and computed getters. Methods and getters run with `this` bound to the scope, and
+// each getter runs once, on first access. Names already in the scope, like props passed as data,
+// are kept. Computed values go through `freeze`, which the sandbox sets.
+(instance, definition, freeze = (value) => value) => {
+    const { props, methods, data, computed } = definition ?? {};
+    const define = (name, value) => {
+        if (!Object.hasOwn(instance, name)) {
+            instance[name] = value;
+        }
+    };
+
+    if (props !== null && typeof props === "object" && !Array.isArray(props)) {
+        for (const [name, prop] of Object.entries(props)) {
+            if (instance[name] !== undefined || prop === null || typeof prop !== "object" || !("default" in prop)) {
+                continue;
+            }
+            // Like Vue, a function default is a factory unless the prop itself is a function
+            const fallback = prop.default;
+            instance[name] = typeof fallback === "function" && prop.type?.name !== "Function"
+                ? fallback.call(instance, { ...instance })
+                : fallback;
+        }
+    }
+
+    for (const [name, method] of Object.entries(methods ?? {})) {
+        if (typeof method === "function") {
+            define(name, method.bind(instance));
+        }
+    }
+
+    if (typeof data === "function") {
+        const state = data.call(instance, instance);
+        for (const [name, value] of Object.entries(state ?? {})) {
+            define(name, value);
+        }
+    }
+
+    for (const [name, getter] of Object.entries(computed ?? {})) {
+        const get = typeof getter === "function" ? getter : getter?.get;
+        if (typeof get !== "function" || Object.hasOwn(instance, name)) {
+            continue;
+        }
+        let value;
+        let done = false;
+        Object.defineProperty(instance, name, {
+            get: () => {
+                if (!done) {
+                    value = freeze(get.call(instance, instance));
+                    done = true;
+                }
+                return value;
+            },
+            enumerable: true,
+            configurable: true,
+        });
+    }
+}
@@ -0,0 +1,358 @@
+use regex::Regex;
+use serde::Serialize;
+use std::sync::LazyLock;
+
+use crate::Options;
+use crate::css;
+use crate::error::TemplateError;
+
+static SYNTAX_ATTRIBUTE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?<name>[^\s=/>]+)(?:\s*=\s*(?:"(?<dq>[^"]*)"|'(?<sq>[^']*)'|(?<uq>[^\s>]+)))?"#)
+        .unwrap()
+});
+static SYNTAX_IMPORT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?m)^[ \t]*import[\s{*"']"#).unwrap());
+static SYNTAX_EXPORT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^[ \t]*export\s+default\s+").unwrap());
+
+/// Vue single-file component
+///
+/// The `<template>` block is the render tree. The `<script>` block may define `props` defaults,
+/// `data()`, `computed` getters and `methods` with `export default { ... }`; it runs in the
+/// render's JavaScript context, so imports and `<script setup>` are not supported. `<style>`
+/// blocks are collected for the page head: selectors of `<style scoped>` blocks are rewritten
+/// with a `[data-v-<hash>]` attribute selector, and every element the component renders gets
+/// that attribute.
+///
+/// # Examples
+///
+/// ```
+/// use prevue::Component;
+/// use serde_json::json;
+///
+/// let source = r#"
+/// <template>
+///   <p>{{ greeting }}, {{ name }}!</p>
+/// </template>
+///
+/// <script>
+/// export default {
+///   props: { name: { default: "World" } },
+///   data() {
+///     return { greeting: "Hello" };
+///   },
+/// };
+/// </script>
+///
+/// <style scoped>
+/// p { color: red; }
+/// </style>
+/// "#;
+/// let component = Component::parse(source).unwrap();
+/// let result = component.render(json!({})).unwrap();
+/// assert!(component.styles()[0].scoped);
+///
+/// let scope = component.scope_id().unwrap();
+/// assert!(result.contains(&format!(r#"<p {scope}="">Hello, World!</p>"#)));
+/// assert_eq!(component.css().trim(), format!("p[{scope}] {{ color: red; }}"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Component {
+    template: String,
+    // Line of the component source where the template content starts
+    template_line: usize,
+    // Definition object, without `export default`
+    script: Option<String>,
+    styles: Vec<Style>,
+    // `data-v-` attribute of the rendered elements, when a style is scoped
+    scope_id: Option<String>,
+}
+
+/// `<style>` block of a [`Component`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Style {
+    /// Style sheet content
+    pub css: String,
+    /// Whether the block has the `scoped` attribute
+    pub scoped: bool,
+    /// Value of the `lang` attribute, like `scss`
+    pub lang: Option<String>,
+}
+
+// Top-level block of a single-file component
+struct Block<'a> {
+    name: &'a str,
+    attrs: Vec<(String, String)>,
+    content: &'a str,
+    // 1-based line where the content starts
+    line: usize,
+}
+
+impl Block<'_> {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(attr, _)| attr == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl Component {
+    /// Parse the source of a `.vue` file
+    pub fn parse(source: &str) -> Result<Self, anyhow::Error> {
+        let mut template = None;
+        let mut script = None;
+        let mut styles = Vec::new();
+
+        for block in blocks(source)? {
+            match block.name {
+                "template" => {
+                    if template.is_some() {
+                        return Err(error(block.line, "duplicate <template> block"));
+                    }
+                    if let Some(lang) = block.attr("lang").filter(|lang| *lang != "html") {
+                        return Err(error(
+                            block.line,
+                            format!("unsupported template lang `{lang}`"),
+                        ));
+                    }
+                    template = Some((block.content.to_string(), block.line));
+                }
+                "script" => {
+                    if block.attr("setup").is_some() {
+                        return Err(error(block.line, "<script setup> is not supported"));
+                    }
+                    if script.is_some() {
+                        return Err(error(block.line, "duplicate <script> block"));
+                    }
+                    script = Some(definition(&block)?);
+                }
+                "style" => styles.push(Style {
+                    css: block.content.to_string(),
+                    scoped: block.attr("scoped").is_some(),
+                    lang: block.attr("lang").map(str::to_string),
+                }),
+                // Custom blocks like `<i18n>` or `<docs>` are for other tools
+                _ => (),
+            }
+        }
+
+        let (template, template_line) = template
+            .ok_or_else(|| TemplateError::new(None, "component has no <template> block"))?;
+        let scope_id = styles
+            .iter()
+            .any(|style| style.scoped)
+            .then(|| format!("data-v-{}", css::hash(source)));
+        Ok(Self {
+            template,
+            template_line,
+            script,
+            styles,
+            scope_id,
+        })
+    }
+
+    /// Content of the `<template>` block
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    /// `<style>` blocks, in source order
+    pub fn styles(&self) -> &[Style] {
+        &self.styles
+    }
+
+    /// Attribute name every rendered element carries, like `data-v-7ba5bd90`, when one of the
+    /// styles is scoped
+    ///
+    /// The hash is derived from the component source, so it is stable between builds.
+    pub fn scope_id(&self) -> Option<&str> {
+        self.scope_id.as_deref()
+    }
+
+    /// Style sheet of the component, with scoped selectors rewritten
+    ///
+    /// Blocks with a preprocessor `lang`, like `scss`, are left out; they are available from
+    /// [`styles`](Self::styles).
+    pub fn css(&self) -> String {
+        let mut sheet = String::new();
+        for style in &self.styles {
+            if style.lang.as_deref().is_some_and(|lang| lang != "css") {
+                continue;
+            }
+            let css = match &self.scope_id {
+                Some(scope_id) if style.scoped => css::scope(&style.css, scope_id),
+                _ => style.css.clone(),
+            };
+            let css = css.trim_matches('\n');
+            if !css.is_empty() {
+                sheet.push_str(css);
+                sheet.push('\n');
+            }
+        }
+        sheet
+    }
+
+    /// Render the component with data as its props
+    pub fn render(&self, data: impl Serialize) -> Result<String, anyhow::Error> {
+        Options::default().render_component(self, data)
+    }
+
+    pub(crate) fn template_line(&self) -> usize {
+        self.template_line
+    }
+
+    pub(crate) fn script(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+}
+
+// Definition object of a `<script>` block: the expression after `export default`
+fn definition(block: &Block) -> Result<String, anyhow::Error> {
+    if let Some(lang) = block
+        .attr("lang")
+        .filter(|lang| !matches!(*lang, "js" | "javascript"))
+    {
+        return Err(error(
+            block.line,
+            format!("unsupported script lang `{lang}`"),
+        ));
+    }
+    if let Some(import) = SYNTAX_IMPORT.find(block.content) {
+        let line = block.line + block.content[..import.start()].matches('\n').count();
+        return Err(error(line, "imports are not supported in <script>"));
+    }
+    if !SYNTAX_EXPORT.is_match(block.content) {
+        return Err(error(
+            block.line,
+            "<script> must define the component with `export default`",
+        ));
+    }
+    Ok(expression(block.content).to_string())
+}
+
+// Definition object of a script, after `export default` when there is one
+pub(crate) fn expression(script: &str) -> &str {
+    let expr = match SYNTAX_EXPORT.find(script) {
+        Some(export) => &script[export.end()..],
+        None => script,
+    };
+    let expr = expr.trim();
+    expr.strip_suffix(';').unwrap_or(expr)
+}
+
+// Top-level blocks of a component source; comments and text between blocks are skipped
+fn blocks(source: &str) -> Result<Vec<Block<'_>>, anyhow::Error> {
+    let mut blocks = Vec::new();
+    let mut cursor = 0;
+
+    while let Some(found) = source[cursor..].find('<') {
+        let start = cursor + found;
+        let rest = &source[start..];
+
+        if rest.starts_with("<!--") {
+            cursor = rest
+                .find("-->")
+                .map(|end| start + end + 3)
+                .ok_or_else(|| error(line_at(source, start), "unterminated comment"))?;
+            continue;
+        }
+
+        let name_len = rest[1..]
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+            .unwrap_or(rest.len() - 1);
+        if name_len == 0 {
+            cursor = start + 1;
+            continue;
+        }
+        let name = &rest[1..1 + name_len];
+
+        let open_end = tag_end(source, start + 1 + name_len)
+            .ok_or_else(|| error(line_at(source, start), format!("unterminated <{name}> tag")))?;
+        let attrs = SYNTAX_ATTRIBUTE
+            .captures_iter(&source[start + 1 + name_len..open_end - 1])
+            .map(|caps| {
+                let value = ["dq", "sq", "uq"]
+                    .iter()
+                    .find_map(|group| caps.name(group))
+                    .map_or("", |m| m.as_str());
+                (caps["name"].to_ascii_lowercase(), value.to_string())
+            })
+            .collect();
+
+        let (content_end, close_end) = close_tag(source, name, open_end).ok_or_else(|| {
+            error(
+                line_at(source, start),
+                format!("<{name}> block is not closed"),
+            )
+        })?;
+
+        blocks.push(Block {
+            name,
+            attrs,
+            content: &source[open_end..content_end],
+            line: line_at(source, open_end),
+        });
+        cursor = close_end;
+    }
+
+    Ok(blocks)
+}
+
+// Position after the `>` ending a tag, skipping quoted attribute values
+fn tag_end(source: &str, from: usize) -> Option<usize> {
+    let bytes = source.as_bytes();
+    let mut i = from;
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'"' | b'\'') => {
+                i += 1 + source[i + 1..].find(quote as char)?;
+            }
+            b'>' => return Some(i + 1),
+            _ => (),
+        }
+        i += 1;
+    }
+    None
+}
+
+// Content end and position after the closing tag of a block. Nested elements of the same name
+// are counted for `<template>`; script and style content is raw text.
+fn close_tag(source: &str, name: &str, from: usize) -> Option<(usize, usize)> {
+    let open = format!("<{name}");
+    let close = format!("</{name}");
+    let lower = source.to_ascii_lowercase();
+    let mut depth = 0;
+    let mut i = from;
+
+    loop {
+        let next_close = i + lower[i..].find(&close)?;
+        let next_open = (name == "template")
+            .then(|| lower[i..].find(&open).map(|found| i + found))
+            .flatten()
+            .filter(|&found| {
+                found < next_close
+                    && lower[found + open.len()..]
+                        .starts_with(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')
+            });
+
+        if let Some(found) = next_open {
+            depth += 1;
+            i = found + open.len();
+            continue;
+        }
+        if depth == 0 {
+            return Some((next_close, tag_end(source, next_close + close.len())?));
+        }
+        depth -= 1;
+        i = next_close + close.len();
+    }
+}
+
+fn line_at(source: &str, offset: usize) -> usize {
+    source[..offset].matches('\n').count() + 1
+}
+
+fn error(line: usize, message: impl Into<String>) -> anyhow::Error {
+    TemplateError::new(Some(line), message).into()
+}
@@ -0,0 +1,259 @@
+// At-rules holding nested rules whose selectors are scoped
+const GROUPING_RULES: &[&str] = &[
+    "media",
+    "supports",
+    "container",
+    "layer",
+    "document",
+    "scope",
+];
+
+// Scope the selectors of a style sheet with the `[attr]` attribute selector, like Vue's
+// `<style scoped>`. The selector's last compound gets the attribute, `:deep(x)` moves it
+// before `x` and `:global(x)` leaves `x` unscoped. Keyframes and other at-rules are kept.
+pub(crate) fn scope(css: &str, attr: &str) -> String {
+    let mut scoped = String::with_capacity(css.len() + css.len() / 4);
+    scope_rules(css, attr, &mut scoped);
+    scoped
+}
+
+// Hash of a component source, for its `data-v-` attribute
+pub(crate) fn hash(source: &str) -> String {
+    // 32-bit FNV-1a, which is stable across builds unlike the std hasher
+    let hash = source.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    });
+    format!("{hash:08x}")
+}
+
+fn scope_rules(css: &str, attr: &str, out: &mut String) {
+    let bytes = css.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i].is_ascii_whitespace() || bytes[i] == b'}' {
+            out.push(bytes[i] as char);
+            i += 1;
+            continue;
+        }
+        if css[i..].starts_with("/*") {
+            let end = skip_comment(css, i);
+            out.push_str(&css[i..end]);
+            i = end;
+            continue;
+        }
+
+        // Prelude of a rule: selectors or an at-rule, up to its block or `;`
+        let start = i;
+        while i < bytes.len() && !matches!(bytes[i], b'{' | b';') {
+            i = skip_token(css, i);
+        }
+        if i == bytes.len() || bytes[i] == b';' {
+            let end = (i + 1).min(bytes.len());
+            out.push_str(&css[start..end]);
+            i = end;
+            continue;
+        }
+
+        let prelude = &css[start..i];
+        let (end, closed) = matching(css, i);
+        let body = &css[i + 1..if closed { end - 1 } else { end }];
+
+        if let Some(at_rule) = prelude.strip_prefix('@') {
+            let name = at_rule
+                .split(|c: char| c.is_ascii_whitespace() || c == '(')
+                .next()
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            if GROUPING_RULES.contains(&name.as_str()) {
+                out.push_str(prelude);
+                out.push('{');
+                scope_rules(body, attr, out);
+                if closed {
+                    out.push('}');
+                }
+            } else {
+                out.push_str(&css[start..end]);
+            }
+        } else {
+            out.push_str(&scope_selectors(prelude, attr));
+            out.push_str(&css[i..end]);
+        }
+        i = end;
+    }
+}
+
+// Scope each selector of a comma-separated list, keeping the whitespace around them
+fn scope_selectors(list: &str, attr: &str) -> String {
+    let trimmed = list.trim_end();
+    let mut scoped = String::with_capacity(list.len() + attr.len() + 2);
+    let mut start = 0;
+    let mut i = 0;
+
+    while i <= trimmed.len() {
+        if i == trimmed.len() || trimmed.as_bytes()[i] == b',' {
+            let selector = &trimmed[start..i];
+            let selector_start = selector.len() - selector.trim_start().len();
+            scoped.push_str(&selector[..selector_start]);
+            scoped.push_str(&scope_selector(selector.trim_start(), attr));
+            if i < trimmed.len() {
+                scoped.push(',');
+            }
+            i += 1;
+            start = i;
+        } else {
+            i = skip_token(trimmed, i);
+        }
+    }
+
+    scoped.push_str(&list[trimmed.len()..]);
+    scoped
+}
+
+fn scope_selector(selector: &str, attr: &str) -> String {
+    if selector.is_empty() {
+        return String::new();
+    }
+    let bytes = selector.as_bytes();
+    // Start of the last compound selector, and end of its last simple selector that isn't a
+    // pseudo-class or pseudo-element
+    let mut compound = 0;
+    let mut last = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            byte if is_combinator(byte) => {
+                while i < bytes.len() && is_combinator(bytes[i]) {
+                    i += 1;
+                }
+                compound = i;
+                last = None;
+            }
+            b':' => {
+                let pseudo = i;
+                while i < bytes.len() && bytes[i] == b':' {
+                    i += 1;
+                }
+                let name_start = i;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'-') {
+                    i += 1;
+                }
+                let name = selector[name_start..i].to_ascii_lowercase();
+                if i < bytes.len() && bytes[i] == b'(' {
+                    let (end, closed) = matching(selector, i);
+                    let inner = selector[i + 1..if closed { end - 1 } else { end }].trim();
+                    match name.as_str() {
+                        "deep" | "v-deep" => {
+                            return deep(&selector[..pseudo], inner, &selector[end..], attr);
+                        }
+                        "global" => {
+                            return format!("{}{inner}{}", &selector[..pseudo], &selector[end..]);
+                        }
+                        _ => i = end,
+                    }
+                }
+            }
+            b'\\' => {
+                i += 1 + selector[i + 1..].chars().next().map_or(0, char::len_utf8);
+                last = Some(i);
+            }
+            _ => {
+                i = skip_token(selector, i);
+                last = Some(i);
+            }
+        }
+    }
+
+    let at = last.unwrap_or(compound);
+    format!("{}[{attr}]{}", &selector[..at], &selector[at..])
+}
+
+// `a :deep(b)` scopes `a` and leaves `b` to match inside child components
+fn deep(prefix: &str, inner: &str, rest: &str, attr: &str) -> String {
+    let core = prefix
+        .trim_end()
+        .trim_end_matches(['>', '+', '~'])
+        .trim_end();
+    let combinator = &prefix[core.len()..];
+    let combinator = if combinator.trim().is_empty() {
+        " "
+    } else {
+        combinator
+    };
+    let scoped = if core.is_empty() {
+        format!("[{attr}]")
+    } else {
+        scope_selector(core, attr)
+    };
+    format!("{scoped}{combinator}{inner}{rest}")
+}
+
+// Whitespace or a combinator between compound selectors
+fn is_combinator(byte: u8) -> bool {
+    byte.is_ascii_whitespace() || matches!(byte, b'>' | b'+' | b'~')
+}
+
+// Position after the token at `i`: a string, comment, bracketed group or single character
+fn skip_token(css: &str, i: usize) -> usize {
+    let bytes = css.as_bytes();
+    match bytes[i] {
+        b'"' | b'\'' => skip_string(css, i),
+        b'(' | b'[' => matching(css, i).0,
+        b'/' if css[i..].starts_with("/*") => skip_comment(css, i),
+        b'\\' => i + 1 + css[i + 1..].chars().next().map_or(0, char::len_utf8),
+        _ => i + css[i..].chars().next().map_or(1, char::len_utf8),
+    }
+}
+
+// Position after the bracket closing the one at `open`, and whether it was found
+fn matching(css: &str, open: usize) -> (usize, bool) {
+    let bytes = css.as_bytes();
+    let mut stack = vec![bytes[open]];
+    let mut i = open + 1;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' | b'(' | b'[' => {
+                stack.push(bytes[i]);
+                i += 1;
+            }
+            close @ (b'}' | b')' | b']') => {
+                let open = match close {
+                    b'}' => b'{',
+                    b')' => b'(',
+                    _ => b'[',
+                };
+                if stack.last() == Some(&open) {
+                    stack.pop();
+                }
+                i += 1;
+                if stack.is_empty() {
+                    return (i, true);
+                }
+            }
+            _ => i = skip_token(css, i),
+        }
+    }
+    (bytes.len(), false)
+}
+
+fn skip_string(css: &str, start: usize) -> usize {
+    let bytes = css.as_bytes();
+    let quote = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            byte if byte == quote || byte == b'\n' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn skip_comment(css: &str, start: usize) -> usize {
+    css[start + 2..]
+        .find("*/")
+        .map_or(css.len(), |end| start + 2 + end + 2)
+}
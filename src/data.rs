@@ -0,0 +1,61 @@
+use serde_json::Value;
+use std::path::Path;
+
+/// Format of a data file
+///
+/// JSON is always available; YAML needs the `yaml` feature and TOML the `toml` feature.
+///
+/// # Examples
+///
+/// ```
+/// use prevue::DataFormat;
+/// use serde_json::json;
+///
+/// let format = DataFormat::from_path("posts.json").unwrap();
+/// let data = format.parse(r#"{ "posts": [] }"#).unwrap();
+/// assert_eq!(data, json!({ "posts": [] }));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataFormat {
+    /// `.json`
+    Json,
+    /// `.yaml` or `.yml`
+    Yaml,
+    /// `.toml`
+    Toml,
+}
+
+impl DataFormat {
+    /// Format named `json`, `yaml`, `yml` or `toml`, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+
+    /// Format of a file, from its extension
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        path.as_ref()
+            .extension()?
+            .to_str()
+            .and_then(Self::from_name)
+    }
+
+    /// Parse data in this format
+    pub fn parse(self, source: &str) -> Result<Value, anyhow::Error> {
+        match self {
+            Self::Json => Ok(serde_json::from_str(source)?),
+            #[cfg(feature = "yaml")]
+            Self::Yaml => Ok(serde_yaml::from_str(source)?),
+            #[cfg(not(feature = "yaml"))]
+            Self::Yaml => anyhow::bail!("YAML data needs the `yaml` feature"),
+            #[cfg(feature = "toml")]
+            Self::Toml => Ok(toml::from_str(source)?),
+            #[cfg(not(feature = "toml"))]
+            Self::Toml => anyhow::bail!("TOML data needs the `toml` feature"),
+        }
+    }
+}
@@ -1,26 +1,369 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
 use std::sync::atomic::AtomicUsize;
+use std::time::UNIX_EPOCH;
 
 use boa_engine::{
-    Context, JsResult, JsString, JsValue, JsVariant, Source, object::ObjectInitializer,
+    Context, JsNativeError, JsObject, JsResult, JsString, JsValue, JsVariant, Source,
+    context::HostHooks, object::ObjectInitializer,
 };
+use markup5ever_rcdom::Handle;
 use serde::Serialize;
 use serde_json::Value as JsonValue;
 
+use crate::error::TemplateError;
+use crate::options::{Delimiters, Options};
+use crate::sink::Lines;
+use crate::warning::{Warning, WarningKind};
+use crate::{Component, Loader};
+
+// Normalize a v-for source into `[value, key, index]` triples, following Vue's `renderList`
+const ITERATE: &str = r#"(source) => {
+    if (Array.isArray(source) || typeof source === "string") {
+        return Array.from(source, (value, index) => [value, index, index]);
+    }
+    if (typeof source === "number") {
+        const length = Number.isFinite(source) ? Math.max(Math.ceil(source), 0) : 0;
+        return Array.from({ length }, (_, index) => [index + 1, index, index]);
+    }
+    if (source !== null && typeof source === "object") {
+        if (typeof source[Symbol.iterator] === "function") {
+            return Array.from(source, (value, index) => [value, index, index]);
+        }
+        return Object.keys(source).map((key, index) => [source[key], key, index]);
+    }
+    return [];
+}"#;
+
+// Own enumerable properties of an object as `[key, value]` pairs
+const ENTRIES: &str = r#"(source) => Object.entries(Object(source))"#;
+
+// Formatting helpers of `Options::prelude`, as an object of functions
+const PRELUDE: &str = include_str!("prelude.js");
+
+// Installs a component definition, as `(instance, definition) => {}`
+const COMPONENT: &str = include_str!("component.js");
+
+// `$t` and `$tc` of `Options::messages`, as `(locale, missing, ...catalogs) => helpers`
+const I18N: &str = include_str!("i18n.js");
+
+// Pin the clock, seed `Math.random` with mulberry32 and default the locale and time zone of
+// `toLocale*` methods and `Intl` formatters; each setting is skipped when undefined. Boa
+// implements neither `Date` `toLocale*String` nor `Intl.DateTimeFormat` formatting, so only
+// numbers, arrays and strings see the locale for now; `Date` local time uses `FixedOffset`
+const DETERMINISM: &str = r#"(now, seed, locale, timeZone) => {
+    // Captured, as the sandbox removes `Reflect` from the global object afterwards
+    const { construct } = Reflect;
+    const { defineProperty } = Object;
+    const define = (target, key, value) =>
+        defineProperty(target, key, { value, writable: true, configurable: true });
+
+    if (now !== undefined) {
+        const RealDate = Date;
+        const FixedDate = function Date(...args) {
+            if (new.target === undefined) {
+                return new RealDate(now).toString();
+            }
+            return construct(RealDate, args.length === 0 ? [now] : args, new.target);
+        };
+        FixedDate.prototype = RealDate.prototype;
+        FixedDate.now = () => now;
+        FixedDate.parse = RealDate.parse;
+        FixedDate.UTC = RealDate.UTC;
+        define(RealDate.prototype, "constructor", FixedDate);
+        define(globalThis, "Date", FixedDate);
+    }
+
+    if (seed !== undefined) {
+        let state = seed | 0;
+        define(Math, "random", function random() {
+            state = (state + 0x6d2b79f5) | 0;
+            let t = Math.imul(state ^ (state >>> 15), 1 | state);
+            t = (t + Math.imul(t ^ (t >>> 7), 61 | t)) ^ t;
+            return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+        });
+    }
+
+    // Fill in missing `locales` at `index`, and a missing `timeZone` in the options after it
+    const defaults = (args, index, zoned) => {
+        if (locale !== undefined && args[index] === undefined) {
+            args[index] = locale;
+        }
+        if (zoned && timeZone !== undefined && args[index + 1]?.timeZone === undefined) {
+            args[index + 1] = { ...args[index + 1], timeZone };
+        }
+        return args;
+    };
+    // Boa's `toLocaleString` of numbers ignores the locale, so numbers use `Intl.NumberFormat`
+    const NumberFormat = typeof Intl === "object" ? Intl.NumberFormat : undefined;
+    const formatNumber = NumberFormat && function toLocaleString(locales, options) {
+        return new NumberFormat(locales, options).format(this.valueOf());
+    };
+    const methods = [
+        [Number.prototype, "toLocaleString", 0, false, formatNumber],
+        [Array.prototype, "toLocaleString", 0, false],
+        [String.prototype, "localeCompare", 1, false],
+        [Date.prototype, "toLocaleString", 0, true],
+        [Date.prototype, "toLocaleDateString", 0, true],
+        [Date.prototype, "toLocaleTimeString", 0, true],
+    ];
+    if (typeof BigInt === "function") {
+        methods.push([BigInt.prototype, "toLocaleString", 0, false, formatNumber]);
+    }
+    for (const [target, key, index, zoned, original = target[key]] of methods) {
+        define(target, key, {
+            [key](...args) {
+                return original.apply(this, defaults(args, index, zoned));
+            },
+        }[key]);
+    }
+
+    if (typeof Intl === "object") {
+        for (const name of Object.getOwnPropertyNames(Intl)) {
+            const Original = Intl[name];
+            if (typeof Original !== "function" || Original.prototype === undefined) {
+                continue;
+            }
+            // A function expression, as methods are not constructors
+            const Wrapped = function (...args) {
+                defaults(args, 0, name === "DateTimeFormat");
+                return construct(Original, args, new.target ?? Original);
+            };
+            defineProperty(Wrapped, "name", { value: name, configurable: true });
+            Wrapped.prototype = Original.prototype;
+            Wrapped.supportedLocalesOf = Original.supportedLocalesOf;
+            define(Original.prototype, "constructor", Wrapped);
+            define(Intl, name, Wrapped);
+        }
+    }
+}"#;
+
+// Harden the realm: block the function constructors, freeze every intrinsic and strip the
+// global object down to the allowlist. Returns `[resolver, freeze]`, where the resolver builds
+// a proxy answering every free identifier from the scopes or the allowlist.
+const SANDBOX: &str = r#"(() => {
+    const allowed = new Set([
+        "Math", "Date", "JSON", "Number", "String", "Array", "Object", "Intl",
+        "Boolean", "Symbol", "Map", "Set", "NaN", "Infinity", "undefined",
+        "isNaN", "isFinite", "parseInt", "parseFloat",
+        "encodeURI", "encodeURIComponent", "decodeURI", "decodeURIComponent",
+    ]);
+    const global = globalThis;
+    const { create, defineProperty, freeze, getOwnPropertyDescriptor, getPrototypeOf, hasOwn } = Object;
+    const ownKeys = Reflect.ownKeys;
+    const ProxyObject = Proxy;
+    const TypeErrorObject = TypeError;
+    const unscopables = Symbol.unscopables;
+
+    const safe = create(null);
+    for (const name of allowed) {
+        safe[name] = global[name];
+    }
+    freeze(safe);
+
+    // Function constructors compile code in the global scope, reachable from any function
+    const blocked = function () {
+        throw new TypeErrorObject("code generation is disabled");
+    };
+    const functions = [function () {}, async function () {}, function* () {}, async function* () {}];
+    for (const fn of functions) {
+        defineProperty(getPrototypeOf(fn), "constructor", { value: blocked });
+    }
+
+    const seen = new WeakSet([global]);
+    const deepFreeze = (value) => {
+        if ((typeof value !== "object" && typeof value !== "function") || value === null || seen.has(value)) {
+            return value;
+        }
+        seen.add(value);
+        freeze(value);
+        for (const key of ownKeys(value)) {
+            const desc = getOwnPropertyDescriptor(value, key);
+            if (hasOwn(desc, "value")) {
+                deepFreeze(desc.value);
+            } else {
+                deepFreeze(desc.get);
+                deepFreeze(desc.set);
+            }
+        }
+        deepFreeze(getPrototypeOf(value));
+        return value;
+    };
+
+    for (const key of ownKeys(global)) {
+        deepFreeze(global[key]);
+    }
+    // Intrinsics only reachable from literals and iterators
+    for (const value of [
+        blocked, ...functions, functions[2](), functions[3](), /./[Symbol.matchAll](""),
+        [][Symbol.iterator](), ""[Symbol.iterator](), new Map().entries(), new Set().values(),
+    ]) {
+        deepFreeze(value);
+    }
+    for (const key of ownKeys(global)) {
+        if (!allowed.has(key)) {
+            delete global[key];
+        }
+    }
+    freeze(global);
+
+    const resolver = (...scopes) => new ProxyObject(create(null), {
+        has: (_, key) => key !== unscopables,
+        get: (_, key) => {
+            for (let i = scopes.length - 1; i >= 0; i--) {
+                if (hasOwn(scopes[i], key)) {
+                    return scopes[i][key];
+                }
+            }
+            return safe[key];
+        },
+        set: () => false,
+        defineProperty: () => false,
+        deleteProperty: () => false,
+    });
+    return [resolver, deepFreeze];
+})()"#;
+
 pub(crate) struct Engine {
     pub context: Context,
+    pub delimiters: Delimiters,
+    pub lines: Lines,
+    // First template error found while rendering
+    pub error: Option<anyhow::Error>,
+    pub warnings: Vec<Warning>,
+    // Source line of the node being processed, for warnings
+    pub line: Option<usize>,
+    // `data-v-` attribute of the elements of the component being rendered
+    pub scope_id: Option<String>,
+    // Style sheets of the rendered components
+    pub styles: Vec<String>,
+    // Components `<component :is>` can render, by name
+    pub components: Arc<HashMap<String, Component>>,
+    // Source of the templates `<include>` inlines
+    pub loader: Arc<Loader>,
+    // Name of the template being rendered, when it was loaded by name
+    pub file: Option<String>,
+    // Included templates being rendered, outermost first
+    pub includes: Vec<String>,
+    // Nesting of the components and includes being rendered
+    pub depth: usize,
+    // Whether fragments and removed branches are marked for Vue's hydration
+    pub hydration: bool,
+    // Content-Security-Policy nonce of the `<script>` and `<style>` elements
+    pub nonce: Option<String>,
+    scopes: Vec<JsObject>,
+    // Number of helper scopes beneath the data, which components see too
+    helper_scopes: usize,
+    // Scope of the render data, the `this` of expressions outside of the sandbox
+    data: Option<JsObject>,
+    // Global names of the scopes, outside of the sandbox
     scope_keys: Vec<String>,
     scope_next: AtomicUsize,
+    sandbox: Option<Sandbox>,
+    // Keys `$t` and `$tc` found no message for, since the last evaluation
+    missing: Option<JsObject>,
+    // Compiled internal functions, keyed by their source
+    helpers: HashMap<&'static str, JsObject>,
+}
+
+// Scopes and template state of the enclosing template, while another one renders inside it
+pub(crate) struct Frame {
+    scopes: Vec<JsObject>,
+    scope_keys: Vec<String>,
+    data: Option<JsObject>,
+    lines: Lines,
+    file: Option<String>,
+    scope_id: Option<String>,
+    // Number of scopes kept when leaving
+    base: usize,
+}
+
+// State of a hardened realm, where scopes are kept out of the global object
+struct Sandbox {
+    // `(...scopes) => proxy` resolving free identifiers from the scopes and the allowlist
+    resolver: JsObject,
+    // Deep freeze of values bound into a scope
+    freeze: JsObject,
+    // Resolver of the current scopes, rebuilt after entering or leaving one
+    proxy: Option<JsObject>,
+    // Compiled expressions, keyed by their source
+    compiled: HashMap<String, JsObject>,
 }
 
 impl Engine {
-    pub fn new(data: impl Serialize) -> Self {
+    pub fn new(
+        data: impl Serialize,
+        lines: Lines,
+        options: &Options,
+    ) -> Result<Self, anyhow::Error> {
+        let delimiters = &options.delimiters;
+        if delimiters.open.is_empty() || delimiters.close.is_empty() {
+            anyhow::bail!("mustache delimiters must not be empty");
+        }
+
+        let mut context = match &options.time_zone {
+            Some(time_zone) => {
+                let offset = utc_offset(time_zone).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "unsupported time zone `{time_zone}`, expected `UTC` or an offset like `+09:00`"
+                    )
+                })?;
+                Context::builder()
+                    .host_hooks(Rc::new(FixedOffset(offset)))
+                    .build()
+                    .map_err(|err| anyhow::anyhow!("{err}"))?
+            }
+            None => Context::default(),
+        };
+        pin_environment(&mut context, options).map_err(|err| anyhow::anyhow!("{err}"))?;
+        let sandbox = if options.sandbox {
+            Some(Sandbox::new(&mut context).map_err(|err| anyhow::anyhow!("{err}"))?)
+        } else {
+            None
+        };
+
         let mut engine = Self {
-            context: Context::default(),
-            scope_keys: Default::default(),
+            context,
+            delimiters: delimiters.clone(),
+            lines,
+            error: None,
+            warnings: Vec::new(),
+            line: None,
+            scope_id: options.scope_id.clone(),
+            styles: options
+                .css
+                .iter()
+                .filter(|css| !css.is_empty())
+                .cloned()
+                .collect(),
+            components: Arc::clone(&options.components),
+            loader: Arc::clone(&options.loader),
+            file: options.file.clone(),
+            includes: options.file.iter().cloned().collect(),
+            depth: 0,
+            hydration: options.hydration,
+            nonce: options.nonce.clone(),
+            scopes: Vec::new(),
+            helper_scopes: 0,
+            data: None,
+            scope_keys: Vec::new(),
             scope_next: AtomicUsize::new(0),
+            sandbox,
+            missing: None,
+            helpers: HashMap::new(),
         };
 
+        // Helpers sit in a scope of their own beneath the data, which can shadow them
+        if options.prelude || !options.messages.is_empty() || options.nonce.is_some() {
+            engine
+                .install_helpers(options)
+                .map_err(|err| anyhow::anyhow!("{err}"))?;
+        }
+
+        engine.helper_scopes = engine.scopes.len();
         engine.enter_scope().unwrap();
+        engine.data = engine.scopes.last().cloned();
 
         if let Ok(json) = serde_json::to_value(data)
             && let Some(obj) = json.as_object()
@@ -32,28 +375,201 @@ impl Engine {
             }
         }
 
-        engine
+        if let Some(script) = &options.script {
+            engine
+                .install_component(script)
+                .map_err(|err| anyhow::anyhow!("{err}"))?;
+        }
+
+        Ok(engine)
     }
 
-    pub fn enter_scope(&mut self) -> JsResult<()> {
-        let key = format!(
-            "__scope_{}",
-            self.scope_next
-                .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    fn install_helpers(&mut self, options: &Options) -> JsResult<()> {
+        self.enter_scope()?;
+        if options.prelude {
+            let helpers = self.context.eval(Source::from_bytes(PRELUDE))?;
+            self.set_entries(helpers)?;
+        }
+        if !options.messages.is_empty() {
+            let locale = options.message_locale();
+            let missing = self
+                .context
+                .eval(Source::from_bytes("[]"))?
+                .as_object()
+                .ok_or_else(|| JsNativeError::typ().with_message("not an array"))?;
+            self.missing = Some(missing.clone());
+            let mut args = vec![JsValue::from(JsString::from(locale)), missing.into()];
+            for catalog in options.message_catalogs() {
+                args.push(JsValue::from_json(catalog, &mut self.context)?);
+            }
+            let messages = self.helper(I18N)?;
+            let helpers = self.call(&messages, &args)?;
+            self.set_entries(helpers)?;
+        }
+        if let Some(nonce) = &options.nonce {
+            self.set_val("$nonce", JsString::from(nonce.as_str()).into());
+        }
+        Ok(())
+    }
+
+    // Define the props defaults, data, computed getters and methods of a component script on the
+    // data scope
+    fn install_component(&mut self, script: &str) -> JsResult<()> {
+        let source = format!(
+            "(() => {{ const defineComponent = (options) => options; return ({script}\n); }})()"
         );
+        let definition = self.context.eval(Source::from_bytes(source.as_bytes()))?;
+        let Some(instance) = self.data.clone() else {
+            return Ok(());
+        };
+        let install = self.helper(COMPONENT)?;
+        let mut args = vec![instance.clone().into(), definition];
+        let freeze = self.sandbox.as_ref().map(|sandbox| sandbox.freeze.clone());
+        if let Some(freeze) = &freeze {
+            args.push(freeze.clone().into());
+        }
+        self.call(&install, &args)?;
+
+        // Sandboxed data is frozen once the component state is in place
+        if let Some(freeze) = freeze {
+            self.call(&freeze, &[instance.into()])?;
+        }
+        Ok(())
+    }
+
+    // Switch to the source lines, name and scoped style attribute of a template rendered inside
+    // the current one. An isolated template only sees the helper scopes; others see the current
+    // scopes too. Scopes entered after this are left by `exit_template`.
+    pub fn enter_template(
+        &mut self,
+        lines: Lines,
+        file: Option<String>,
+        scope_id: Option<String>,
+        isolated: bool,
+    ) -> Frame {
+        let frame = Frame {
+            scopes: self.scopes.clone(),
+            scope_keys: self.scope_keys.clone(),
+            data: self.data.clone(),
+            lines: std::mem::replace(&mut self.lines, lines),
+            file: std::mem::replace(&mut self.file, file),
+            scope_id: std::mem::replace(&mut self.scope_id, scope_id),
+            base: if isolated {
+                self.helper_scopes
+            } else {
+                self.scopes.len()
+            },
+        };
+        if isolated {
+            self.scopes.truncate(self.helper_scopes);
+            self.scope_keys.truncate(self.helper_scopes);
+            self.data = None;
+            if let Some(sandbox) = &mut self.sandbox {
+                sandbox.proxy = None;
+            }
+        }
+        frame
+    }
+
+    // Leave a template, back to the state before `enter_template`
+    pub fn exit_template(&mut self, frame: Frame) {
+        while self.scopes.len() > frame.base {
+            self.exit_scope();
+        }
+        self.scopes = frame.scopes;
+        self.scope_keys = frame.scope_keys;
+        self.data = frame.data;
+        self.lines = frame.lines;
+        self.file = frame.file;
+        self.scope_id = frame.scope_id;
+        if let Some(sandbox) = &mut self.sandbox {
+            sandbox.proxy = None;
+        }
+    }
+
+    // Bind the props, data, computed getters and methods of a component in a scope of its own,
+    // which is also the `this` of its expressions
+    pub fn enter_component(
+        &mut self,
+        props: Vec<(String, JsValue)>,
+        script: Option<&str>,
+    ) -> JsResult<()> {
+        self.enter_scope()?;
+        self.data = self.scopes.last().cloned();
+        for (name, value) in props {
+            self.set_val(&name, value);
+        }
+        match script {
+            Some(script) => self.install_component(script),
+            None => Ok(()),
+        }
+    }
+
+    // Bind each property of an object in the current scope
+    fn set_entries(&mut self, object: JsValue) -> JsResult<()> {
+        for (name, value) in self.entries(object)? {
+            self.set_val(&name, value);
+        }
+        Ok(())
+    }
+
+    // Record a warning at the current line, once
+    pub fn warn(&mut self, kind: WarningKind, message: impl Into<String>) {
+        let mut warning = Warning::new(kind, self.line, message);
+        warning.file = self.file.clone();
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+
+    // Track the source line of a node about to be processed
+    pub fn at(&mut self, node: &Handle) {
+        if let Some(line) = self.lines.get(node) {
+            self.line = Some(line);
+        }
+    }
+
+    // Record an error, keeping only the first one. Template errors get the name of the
+    // template being rendered.
+    pub fn fail(&mut self, error: impl Into<anyhow::Error>) {
+        if self.error.is_none() {
+            let mut error = error.into();
+            if let Some(template) = error.downcast_mut::<TemplateError>()
+                && template.file.is_none()
+            {
+                template.file = self.file.clone();
+            }
+            self.error = Some(error);
+        }
+    }
+
+    pub fn enter_scope(&mut self) -> JsResult<()> {
         let scope = ObjectInitializer::new(&mut self.context).build();
-        self.context.global_object().set(
-            JsString::from(key.as_str()),
-            scope,
-            false,
-            &mut self.context,
-        )?;
-        self.scope_keys.push(key);
+        if let Some(sandbox) = &mut self.sandbox {
+            sandbox.proxy = None;
+        } else {
+            let key = format!(
+                "__scope_{}",
+                self.scope_next
+                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+            );
+            self.context.global_object().set(
+                JsString::from(key.as_str()),
+                scope.clone(),
+                false,
+                &mut self.context,
+            )?;
+            self.scope_keys.push(key);
+        }
+        self.scopes.push(scope);
         Ok(())
     }
 
     pub fn exit_scope(&mut self) {
-        if let Some(key) = self.scope_keys.pop() {
+        self.scopes.pop();
+        if let Some(sandbox) = &mut self.sandbox {
+            sandbox.proxy = None;
+        } else if let Some(key) = self.scope_keys.pop() {
             let _ = self
                 .context
                 .global_object()
@@ -61,28 +577,133 @@ impl Engine {
         }
     }
 
-    pub fn set_val(&mut self, key: &str, value: JsValue) {
-        let mut scope = self.context.global_object();
-
-        if let Some(scope_key) = self.scope_keys.last()
-            && let Ok(scope_val) = scope.get(JsString::from(scope_key.as_str()), &mut self.context)
-            && let Some(local) = scope_val.as_object()
-        {
-            scope = local;
+    pub fn set_val(&mut self, key: &str, mut value: JsValue) {
+        // Sandboxed data is frozen, so one scope can't change what another one sees
+        if let Some(sandbox) = &self.sandbox {
+            let freeze = sandbox.freeze.clone();
+            match self.call(&freeze, &[value]) {
+                Ok(frozen) => value = frozen,
+                Err(_) => return,
+            }
         }
 
+        let scope = self
+            .scopes
+            .last()
+            .cloned()
+            .unwrap_or_else(|| self.context.global_object());
         let _ = scope.set(JsString::from(key), value, false, &mut self.context);
     }
 
     pub fn eval(&mut self, code: &str) -> JsResult<JsValue> {
+        let result = if self.sandbox.is_some() {
+            self.eval_sandboxed(code)
+        } else {
+            self.eval_code(code)
+        };
+        if let Err(err) = &result {
+            self.warn(WarningKind::EvalError, format!("`{}`: {err}", code.trim()));
+        }
+        self.report_missing();
+        result
+    }
+
+    // Warn about the keys `$t` and `$tc` found no message for
+    fn report_missing(&mut self) {
+        let Some(missing) = self.missing.clone() else {
+            return;
+        };
+        let Ok(keys) = self.items(&JsValue::from(missing.clone())) else {
+            return;
+        };
+        if keys.is_empty() {
+            return;
+        }
+        for key in keys {
+            if let Some(key) = self.fmt(key) {
+                self.warn(
+                    WarningKind::MissingMessage,
+                    format!("no message for `{key}`"),
+                );
+            }
+        }
+        let _ = missing.set(JsString::from("length"), 0, false, &mut self.context);
+    }
+
+    fn eval_code(&mut self, code: &str) -> JsResult<JsValue> {
+        // Read the code as an expression first, so `{ a: 1 }` is an object literal rather than
+        // a block, and fall back to statements when it isn't one
+        match self.eval_scoped(&format!("return ({code}\n);")) {
+            Err(err) if err.as_native().is_some_and(JsNativeError::is_syntax) => {
+                let source = serde_json::to_string(code)
+                    .map_err(|err| JsNativeError::syntax().with_message(err.to_string()))?;
+                self.eval_scoped(&format!("return eval({source});"))
+            }
+            result => result,
+        }
+    }
+
+    // Evaluate a single expression with every free identifier answered by the resolver,
+    // which is also its `this`
+    fn eval_sandboxed(&mut self, code: &str) -> JsResult<JsValue> {
+        let Some(sandbox) = &mut self.sandbox else {
+            return Err(JsNativeError::error()
+                .with_message("engine is not sandboxed")
+                .into());
+        };
+
+        let function = match sandbox.compiled.get(code) {
+            Some(function) => function.clone(),
+            None => {
+                let source =
+                    format!("(function (scope) {{ with (scope) {{ return ({code}\n); }} }})");
+                let function = self
+                    .context
+                    .eval(Source::from_bytes(source.as_bytes()))?
+                    .as_object()
+                    .ok_or_else(|| JsNativeError::syntax().with_message("not an expression"))?;
+                sandbox.compiled.insert(code.to_string(), function.clone());
+                function
+            }
+        };
+
+        let proxy = match &sandbox.proxy {
+            Some(proxy) => proxy.clone(),
+            None => {
+                let scopes: Vec<JsValue> = self.scopes.iter().cloned().map(JsValue::from).collect();
+                let proxy = sandbox
+                    .resolver
+                    .call(&JsValue::undefined(), &scopes, &mut self.context)?
+                    .as_object()
+                    .ok_or_else(|| {
+                        JsNativeError::typ().with_message("resolver is not an object")
+                    })?;
+                sandbox.proxy = Some(proxy.clone());
+                proxy
+            }
+        };
+
+        let proxy = JsValue::from(proxy);
+        function.call(&proxy, std::slice::from_ref(&proxy), &mut self.context)
+    }
+
+    // Run a function body inside the scopes, with `this` bound to the data
+    fn eval_scoped(&mut self, body: &str) -> JsResult<JsValue> {
         let scoped = self
             .scope_keys
             .iter()
             .rev()
-            .fold(code.to_string(), |acc, key| {
+            .fold(body.to_string(), |acc, key| {
                 format!(r#"with (globalThis["{key}"]) {{ {acc} }}"#)
             });
-        let evaluated = self.context.eval(Source::from_bytes(scoped.as_bytes()))?;
+        let source = format!("(function () {{ {scoped} }})");
+        let function = self
+            .context
+            .eval(Source::from_bytes(source.as_bytes()))?
+            .as_object()
+            .ok_or_else(|| JsNativeError::syntax().with_message("not a function"))?;
+        let this = self.data.clone().map(JsValue::from).unwrap_or_default();
+        let evaluated = function.call(&this, &[], &mut self.context)?;
 
         if evaluated.equals(
             &JsValue::new(self.context.global_object()),
@@ -109,6 +730,12 @@ impl Engine {
     }
 
     pub fn eval_fmt(&mut self, code: &str) -> Option<String> {
+        let value = self.eval(code).ok()?;
+        self.fmt(value)
+    }
+
+    // Display a value; null and undefined have no display
+    pub fn fmt(&mut self, value: JsValue) -> Option<String> {
         fn fmt(val: &JsonValue) -> String {
             match val {
                 JsonValue::Null => "null".to_string(),
@@ -136,7 +763,6 @@ impl Engine {
             }
         }
 
-        let value = self.eval(code).ok()?;
         match value.variant() {
             JsVariant::Null | JsVariant::Undefined => None,
             JsVariant::String(val) => Some(val.to_std_string_escaped()),
@@ -148,7 +774,168 @@ impl Engine {
         }
     }
 
+    // Call a function with the given arguments
+    pub fn call(&mut self, function: &JsObject, args: &[JsValue]) -> JsResult<JsValue> {
+        function.call(&JsValue::undefined(), args, &mut self.context)
+    }
+
+    // Elements of an array-like value
+    pub fn items(&mut self, value: &JsValue) -> JsResult<Vec<JsValue>> {
+        let Some(obj) = value.as_object() else {
+            return Ok(Vec::new());
+        };
+        let length = obj
+            .get(JsString::from("length"), &mut self.context)?
+            .to_length(&mut self.context)?;
+        (0..length)
+            .map(|i| obj.get(i as u32, &mut self.context))
+            .collect()
+    }
+
+    // Entries of a v-for source as `[value, key, index]`, supporting arrays, strings, numbers,
+    // any object with `Symbol.iterator` and plain objects
+    pub fn iterate(&mut self, source: JsValue) -> JsResult<Vec<[JsValue; 3]>> {
+        let iterate = self.helper(ITERATE)?;
+        let entries = self.call(&iterate, &[source])?;
+        self.items(&entries)?
+            .iter()
+            .map(|entry| {
+                let mut triple = self.items(entry)?.into_iter();
+                Ok([(); 3].map(|_| triple.next().unwrap_or_default()))
+            })
+            .collect()
+    }
+
+    // Own enumerable properties of an object
+    pub fn entries(&mut self, value: JsValue) -> JsResult<Vec<(String, JsValue)>> {
+        let entries = self.helper(ENTRIES)?;
+        let entries = self.call(&entries, &[value])?;
+        self.items(&entries)?
+            .iter()
+            .map(|entry| {
+                let mut pair = self.items(entry)?.into_iter();
+                let key = pair
+                    .next()
+                    .unwrap_or_default()
+                    .to_string(&mut self.context)?
+                    .to_std_string_escaped();
+                Ok((key, pair.next().unwrap_or_default()))
+            })
+            .collect()
+    }
+
+    // Compile an internal function once per render
+    fn helper(&mut self, source: &'static str) -> JsResult<JsObject> {
+        if let Some(helper) = self.helpers.get(source) {
+            return Ok(helper.clone());
+        }
+        let helper = self
+            .context
+            .eval(Source::from_bytes(source))?
+            .as_object()
+            .ok_or_else(|| JsNativeError::typ().with_message("helper is not a function"))?;
+        self.helpers.insert(source, helper.clone());
+        Ok(helper)
+    }
+
     pub fn eval_bool(&mut self, code: &str) -> Option<bool> {
         Some(self.eval(code).ok()?.to_boolean())
     }
 }
+
+impl Sandbox {
+    fn new(context: &mut Context) -> JsResult<Self> {
+        let hardened = context.eval(Source::from_bytes(SANDBOX))?;
+        let function = |index: u32, context: &mut Context| {
+            hardened
+                .as_object()
+                .and_then(|pair| pair.get(index, context).ok())
+                .and_then(|value| value.as_object())
+                .ok_or_else(|| JsNativeError::typ().with_message("sandbox setup failed"))
+        };
+        Ok(Self {
+            resolver: function(0, context)?,
+            freeze: function(1, context)?,
+            proxy: None,
+            compiled: HashMap::new(),
+        })
+    }
+}
+
+// Host hooks placing the local time of `Date` at a fixed offset from UTC, in seconds
+struct FixedOffset(i32);
+
+impl HostHooks for FixedOffset {
+    fn local_timezone_offset_seconds(&self, _unix_time_seconds: i64) -> i32 {
+        self.0
+    }
+}
+
+// Offset in seconds of `UTC` or an offset like `+09:00`, `-0530` or `+01`
+fn utc_offset(time_zone: &str) -> Option<i32> {
+    if ["UTC", "Etc/UTC", "GMT", "Etc/GMT", "Z"]
+        .iter()
+        .any(|name| time_zone.eq_ignore_ascii_case(name))
+    {
+        return Some(0);
+    }
+    let (sign, offset) = match time_zone.split_at_checked(1)? {
+        ("+", offset) => (1, offset),
+        ("-", offset) => (-1, offset),
+        _ => return None,
+    };
+    let (hours, minutes) = match offset.len() {
+        2 => (offset, "00"),
+        4 => offset.split_at(2),
+        5 => offset.split_once(':')?,
+        _ => return None,
+    };
+    let number = |digits: &str, max: i32| {
+        digits
+            .bytes()
+            .all(|b| b.is_ascii_digit())
+            .then(|| digits.parse::<i32>().ok())
+            .flatten()
+            .filter(|&n| n <= max)
+    };
+    Some(sign * (number(hours, 23)? * 3600 + number(minutes, 59)? * 60))
+}
+
+// Apply the clock, random seed, locale and time zone of the options to a new realm
+fn pin_environment(context: &mut Context, options: &Options) -> JsResult<()> {
+    if options.now.is_none()
+        && options.seed.is_none()
+        && options.locale.is_none()
+        && options.time_zone.is_none()
+    {
+        return Ok(());
+    }
+
+    let now = options.now.map(|now| match now.duration_since(UNIX_EPOCH) {
+        Ok(since) => JsValue::from(since.as_millis() as f64),
+        Err(err) => JsValue::from(-(err.duration().as_millis() as f64)),
+    });
+    // mulberry32 keeps 32 bits of state
+    let seed = options
+        .seed
+        .map(|seed| JsValue::from((seed ^ (seed >> 32)) as u32));
+    let string = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(|value| JsValue::from(JsString::from(value)))
+    };
+
+    let pin = context
+        .eval(Source::from_bytes(DETERMINISM))?
+        .as_object()
+        .ok_or_else(|| JsNativeError::typ().with_message("environment setup failed"))?;
+    let args = [
+        now,
+        seed,
+        string(&options.locale),
+        string(&options.time_zone),
+    ]
+    .map(Option::unwrap_or_default);
+    pin.call(&JsValue::undefined(), &args, context)?;
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use std::fmt;
+
+/// Error in the template source
+///
+/// Returned inside [`anyhow::Error`]; use [`anyhow::Error::downcast_ref`] to get the position.
+///
+/// # Examples
+///
+/// ```
+/// use prevue::{TemplateError, render};
+/// use serde_json::json;
+///
+/// let html = "<div>\n  {{ unclosed }\n</div>";
+/// let error = render(html.to_string(), json!({})).unwrap_err();
+/// let error = error.downcast_ref::<TemplateError>().unwrap();
+/// assert_eq!(error.line, Some(2));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TemplateError {
+    /// Name of the template the error is in, when it was loaded by name, like an include
+    pub file: Option<String>,
+    /// 1-based line in the template source, if known
+    pub line: Option<usize>,
+    /// Description of the problem
+    pub message: String,
+}
+
+impl TemplateError {
+    pub(crate) fn new(line: Option<usize>, message: impl Into<String>) -> Self {
+        Self {
+            file: None,
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{} at {}:{}", self.message, file, line),
+            (Some(file), None) => write!(f, "{} in {}", self.message, file),
+            (None, Some(line)) => write!(f, "{} at line {}", self.message, line),
+            (None, None) => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
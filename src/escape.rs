@@ -0,0 +1,109 @@
+use markup5ever_rcdom::{Handle, NodeData};
+use serde_json::Value as JsonValue;
+
+// Replacement for URLs with a scheme that could run script
+const BLOCKED_URL: &str = "about:invalid#blocked";
+
+// Attributes holding a URL that is followed or loaded
+const URL_ATTRIBUTES: [&str; 8] = [
+    "action",
+    "background",
+    "cite",
+    "formaction",
+    "href",
+    "poster",
+    "src",
+    "xlink:href",
+];
+
+// Where interpolated text ends up, deciding how it is escaped
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TextContext {
+    Html,
+    Script,
+    Style,
+}
+
+// Context of a text node, from its parent element. Interpolations in any `<script>` are
+// written as JSON, which is a valid JavaScript literal too
+pub(crate) fn text_context(node: &Handle) -> TextContext {
+    let Some(parent) = node.parent.take() else {
+        return TextContext::Html;
+    };
+    node.parent.set(Some(parent.clone()));
+    let Some(parent) = parent.upgrade() else {
+        return TextContext::Html;
+    };
+    let NodeData::Element { name, .. } = &parent.data else {
+        return TextContext::Html;
+    };
+
+    match name.local.as_ref() {
+        "style" => TextContext::Style,
+        "script" => TextContext::Script,
+        _ => TextContext::Html,
+    }
+}
+
+// JSON text that can't end a `<script>` element or open a comment inside it
+pub(crate) fn json(value: &JsonValue) -> String {
+    escape_chars(&value.to_string(), is_html_significant)
+}
+
+// Characters that could end a `<script>` element, start a comment in it or break a line in
+// older JavaScript engines
+fn is_html_significant(c: char) -> bool {
+    matches!(c, '<' | '>' | '&' | '\u{2028}' | '\u{2029}')
+}
+
+fn escape_chars(text: &str, escape: impl Fn(char) -> bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if escape(c) {
+            escaped.push_str(&format!("\\u{:04x}", c as u32));
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+// CSS value with characters that could end a declaration, rule or element escaped. Quotes
+// are kept, so font families and `url('...')` stay intact
+pub(crate) fn css(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_control() || matches!(c, '\\' | ';' | '{' | '}' | '<') {
+            escaped.push_str(&format!("\\{:x} ", c as u32));
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+pub(crate) fn is_url_attribute(name: &str) -> bool {
+    URL_ATTRIBUTES.contains(&name)
+}
+
+// URL with `javascript:`, `vbscript:` and `data:` schemes blocked; raster `data:image/` is kept
+pub(crate) fn url(value: String) -> String {
+    // Browsers ignore whitespace and control characters when reading the scheme
+    let scheme: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .take(16)
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    let is_unsafe = scheme.starts_with("javascript:")
+        || scheme.starts_with("vbscript:")
+        || (scheme.starts_with("data:")
+            && (!scheme.starts_with("data:image/") || scheme.starts_with("data:image/svg")));
+
+    if is_unsafe {
+        BLOCKED_URL.to_string()
+    } else {
+        value
+    }
+}
@@ -0,0 +1,449 @@
+use html5ever::QualName;
+use html5ever::serialize::{
+    AttrRef, Serialize, SerializeOpts, Serializer, TraversalScope, serialize as html_serialize,
+};
+use html5ever::tendril::StrTendril;
+use markup5ever_rcdom::{Handle, Node, NodeData, SerializableHandle};
+use std::cell::RefCell;
+use std::io;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use crate::Whitespace;
+
+// Elements whose content is never reformatted
+const PRESERVED: [&str; 4] = ["pre", "textarea", "script", "style"];
+
+// Elements laid out inline; pretty-printing keeps their parent on one line
+const INLINE: [&str; 27] = [
+    "a", "abbr", "b", "bdi", "bdo", "br", "button", "cite", "code", "data", "dfn", "em", "i",
+    "img", "input", "kbd", "label", "mark", "q", "s", "samp", "select", "small", "span", "strong",
+    "sub", "sup",
+];
+
+// Elements without content or end tag
+const VOID: [&str; 18] = [
+    "area", "base", "basefont", "bgsound", "br", "col", "embed", "frame", "hr", "img", "input",
+    "keygen", "link", "meta", "param", "source", "track", "wbr",
+];
+
+// Elements whose text content is written unescaped
+const RAW_TEXT: [&str; 7] = [
+    "style",
+    "script",
+    "xmp",
+    "iframe",
+    "noembed",
+    "noframes",
+    "plaintext",
+];
+
+const HTML_NS: &str = "http://www.w3.org/1999/xhtml";
+
+// Apply a whitespace mode to a rendered subtree
+pub(crate) fn format(node: &Handle, whitespace: Whitespace) {
+    match whitespace {
+        Whitespace::Preserve => {}
+        Whitespace::Condense => condense(node),
+        Whitespace::Minify => {
+            remove_comments(node);
+            condense(node);
+        }
+        Whitespace::Pretty => prettify(node, 0),
+    }
+}
+
+// Apply a whitespace mode to a run of final children of `parent`, returning the nodes to write.
+// Used when streaming, where the rest of the parent may not be rendered yet.
+pub(crate) fn format_siblings(
+    parent: &Handle,
+    nodes: &[Handle],
+    depth: usize,
+    whitespace: Whitespace,
+) -> Vec<Handle> {
+    match whitespace {
+        Whitespace::Preserve => nodes.to_vec(),
+        Whitespace::Condense | Whitespace::Minify => {
+            let minify = whitespace == Whitespace::Minify;
+            let siblings: Vec<Handle> = parent
+                .children
+                .borrow()
+                .iter()
+                .filter(|c| !(minify && is_comment(c)))
+                .cloned()
+                .collect();
+            let range: Vec<usize> = nodes
+                .iter()
+                .filter_map(|node| siblings.iter().position(|c| Rc::ptr_eq(c, node)))
+                .collect();
+            if minify {
+                for node in nodes {
+                    remove_comments(node);
+                }
+            }
+            condense_siblings(&siblings, range)
+        }
+        Whitespace::Pretty => {
+            let is_document = matches!(parent.data, NodeData::Document);
+            let mut formatted = Vec::new();
+            for node in nodes.iter().filter(|c| !is_whitespace_text(c)) {
+                if is_document {
+                    prettify(node, depth);
+                    formatted.push(Rc::clone(node));
+                    formatted.push(text_node("\n"));
+                    continue;
+                }
+                if let NodeData::Text { contents } = &node.data {
+                    let text = collapse(&contents.borrow());
+                    contents.replace(StrTendril::from_str(&text).unwrap());
+                } else {
+                    formatted.push(text_node(&newline(depth + 1)));
+                    prettify(node, depth + 1);
+                }
+                formatted.push(Rc::clone(node));
+            }
+            formatted
+        }
+    }
+}
+
+// Line break followed by the indentation of the given depth
+pub(crate) fn newline(depth: usize) -> String {
+    format!("\n{}", "  ".repeat(depth))
+}
+
+// Serialize a node, dropping optional attribute quotes in minify mode
+pub(crate) fn serialize(
+    writer: impl io::Write,
+    node: &SerializableHandle,
+    traversal_scope: TraversalScope,
+    whitespace: Whitespace,
+) -> io::Result<()> {
+    if whitespace == Whitespace::Minify {
+        let mut serializer = MinifySerializer::new(writer);
+        node.serialize(&mut serializer, traversal_scope)
+    } else {
+        html_serialize(
+            writer,
+            node,
+            SerializeOpts {
+                traversal_scope,
+                ..Default::default()
+            },
+        )
+    }
+}
+
+// Write the start tag of an element the way minify mode does
+pub(crate) fn start_tag<'a>(
+    writer: impl io::Write,
+    name: QualName,
+    attrs: impl Iterator<Item = AttrRef<'a>>,
+) -> io::Result<()> {
+    MinifySerializer::new(writer).start_elem(name, attrs)
+}
+
+fn condense(node: &Handle) {
+    if is_preserved(node) {
+        return;
+    }
+    let children: Vec<Handle> = node.children.borrow().clone();
+    let kept = condense_siblings(&children, 0..children.len());
+    *node.children.borrow_mut() = kept;
+}
+
+// Vue's condense rule for whitespace between siblings
+fn condense_siblings(siblings: &[Handle], range: impl IntoIterator<Item = usize>) -> Vec<Handle> {
+    let mut kept = Vec::new();
+
+    for i in range {
+        let node = &siblings[i];
+        match &node.data {
+            NodeData::Text { contents } => {
+                let text = contents.borrow().to_string();
+                if text.chars().all(is_html_whitespace) {
+                    let prev = i.checked_sub(1).and_then(|i| siblings.get(i));
+                    let next = siblings.get(i + 1);
+                    let remove = match (prev, next) {
+                        (Some(prev), Some(next)) => {
+                            (is_comment(prev) && (is_comment(next) || is_element(next)))
+                                || (is_element(prev) && is_comment(next))
+                                || (is_element(prev) && is_element(next) && text.contains('\n'))
+                        }
+                        _ => true,
+                    };
+                    if remove {
+                        continue;
+                    }
+                    contents.replace(StrTendril::from_str(" ").unwrap());
+                } else {
+                    contents.replace(StrTendril::from_str(&collapse(&text)).unwrap());
+                }
+            }
+            NodeData::Element { .. } => condense(node),
+            _ => {}
+        }
+        kept.push(Rc::clone(node));
+    }
+
+    kept
+}
+
+fn remove_comments(node: &Handle) {
+    let mut children = node.children.borrow_mut();
+    children.retain(|c| !is_comment(c) || is_hydration_marker(c));
+
+    // Merge text nodes that were separated by a comment
+    let mut merged: Vec<Handle> = Vec::with_capacity(children.len());
+    for child in children.drain(..) {
+        if let (Some(NodeData::Text { contents: prev }), NodeData::Text { contents }) =
+            (merged.last().map(|n| &n.data), &child.data)
+        {
+            prev.borrow_mut().push_tendril(&contents.borrow());
+            continue;
+        }
+        merged.push(child);
+    }
+    *children = merged;
+
+    for child in children.iter() {
+        remove_comments(child);
+    }
+}
+
+fn prettify(node: &Handle, depth: usize) {
+    if is_preserved(node) {
+        return;
+    }
+
+    let is_document = matches!(node.data, NodeData::Document);
+    let children: Vec<Handle> = node.children.borrow().clone();
+
+    // Mixed content stays on one line
+    if !is_document && children.iter().any(is_inline) {
+        condense(node);
+        return;
+    }
+
+    let significant: Vec<Handle> = children
+        .into_iter()
+        .filter(|c| !is_whitespace_text(c))
+        .collect();
+
+    let mut formatted = Vec::new();
+    for (i, child) in significant.iter().enumerate() {
+        if is_document {
+            if i > 0 {
+                formatted.push(text_node("\n"));
+            }
+            prettify(child, depth);
+        } else {
+            formatted.push(text_node(&newline(depth + 1)));
+            prettify(child, depth + 1);
+        }
+        formatted.push(Rc::clone(child));
+    }
+    if !is_document && !significant.is_empty() {
+        formatted.push(text_node(&newline(depth)));
+    }
+
+    for child in formatted.iter() {
+        child.parent.set(Some(Rc::downgrade(node)));
+    }
+    *node.children.borrow_mut() = formatted;
+}
+
+// Collapse runs of whitespace into a single space
+fn collapse(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_whitespace = false;
+    for c in text.chars() {
+        if is_html_whitespace(c) {
+            if !in_whitespace {
+                result.push(' ');
+            }
+            in_whitespace = true;
+        } else {
+            result.push(c);
+            in_whitespace = false;
+        }
+    }
+    result
+}
+
+fn text_node(text: &str) -> Handle {
+    Node::new(NodeData::Text {
+        contents: RefCell::new(StrTendril::from_str(text).unwrap()),
+    })
+}
+
+fn is_html_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0C')
+}
+
+fn is_whitespace_text(node: &Handle) -> bool {
+    match &node.data {
+        NodeData::Text { contents } => contents.borrow().chars().all(is_html_whitespace),
+        _ => false,
+    }
+}
+
+fn is_comment(node: &Handle) -> bool {
+    matches!(node.data, NodeData::Comment { .. })
+}
+
+// Comments Vue's client runtime hydrates fragments and removed branches with
+fn is_hydration_marker(node: &Handle) -> bool {
+    matches!(&node.data, NodeData::Comment { contents } if matches!(&**contents, "[" | "]" | "v-if"))
+}
+
+fn is_element(node: &Handle) -> bool {
+    matches!(node.data, NodeData::Element { .. })
+}
+
+fn is_inline(node: &Handle) -> bool {
+    match &node.data {
+        NodeData::Text { .. } => !is_whitespace_text(node),
+        NodeData::Element { name, .. } => INLINE.contains(&name.local.as_ref()),
+        _ => false,
+    }
+}
+
+fn is_preserved(node: &Handle) -> bool {
+    match &node.data {
+        NodeData::Element { name, .. } => PRESERVED.contains(&name.local.as_ref()),
+        _ => false,
+    }
+}
+
+struct ElemInfo {
+    html_name: Option<String>,
+    ignore_children: bool,
+}
+
+// HTML serializer that writes attribute values without quotes where the syntax allows it
+struct MinifySerializer<Wr: io::Write> {
+    writer: Wr,
+    stack: Vec<ElemInfo>,
+}
+
+impl<Wr: io::Write> MinifySerializer<Wr> {
+    fn new(writer: Wr) -> Self {
+        Self {
+            writer,
+            stack: vec![ElemInfo {
+                html_name: None,
+                ignore_children: false,
+            }],
+        }
+    }
+
+    fn parent(&self) -> Option<&ElemInfo> {
+        self.stack.last()
+    }
+
+    fn write_escaped(&mut self, text: &str, attr_mode: bool) -> io::Result<()> {
+        for c in text.chars() {
+            match c {
+                '&' => self.writer.write_all(b"&amp;"),
+                '\u{00A0}' => self.writer.write_all(b"&nbsp;"),
+                '"' if attr_mode => self.writer.write_all(b"&quot;"),
+                '<' => self.writer.write_all(b"&lt;"),
+                '>' => self.writer.write_all(b"&gt;"),
+                c => write!(self.writer, "{c}"),
+            }?;
+        }
+        Ok(())
+    }
+}
+
+impl<Wr: io::Write> Serializer for MinifySerializer<Wr> {
+    fn start_elem<'a, AttrIter>(&mut self, name: QualName, attrs: AttrIter) -> io::Result<()>
+    where
+        AttrIter: Iterator<Item = AttrRef<'a>>,
+    {
+        let html_name = (&*name.ns == HTML_NS).then(|| name.local.to_string());
+
+        if self.parent().is_some_and(|p| p.ignore_children) {
+            self.stack.push(ElemInfo {
+                html_name,
+                ignore_children: true,
+            });
+            return Ok(());
+        }
+
+        write!(self.writer, "<{}", name.local)?;
+        for (name, value) in attrs {
+            self.writer.write_all(b" ")?;
+            match &*name.ns {
+                "" => {}
+                "http://www.w3.org/XML/1998/namespace" => self.writer.write_all(b"xml:")?,
+                "http://www.w3.org/2000/xmlns/" => {
+                    if name.local.as_ref() != "xmlns" {
+                        self.writer.write_all(b"xmlns:")?;
+                    }
+                }
+                "http://www.w3.org/1999/xlink" => self.writer.write_all(b"xlink:")?,
+                _ => self.writer.write_all(b"unknown_namespace:")?,
+            }
+            self.writer.write_all(name.local.as_bytes())?;
+
+            if value.is_empty() {
+                continue;
+            }
+            let needs_quotes = value
+                .chars()
+                .any(|c| is_html_whitespace(c) || matches!(c, '"' | '\'' | '=' | '<' | '>' | '`'));
+            if needs_quotes {
+                self.writer.write_all(b"=\"")?;
+                self.write_escaped(value, true)?;
+                self.writer.write_all(b"\"")?;
+            } else {
+                self.writer.write_all(b"=")?;
+                self.write_escaped(value, true)?;
+            }
+        }
+        self.writer.write_all(b">")?;
+
+        let ignore_children = html_name
+            .as_deref()
+            .is_some_and(|local| VOID.contains(&local));
+        self.stack.push(ElemInfo {
+            html_name,
+            ignore_children,
+        });
+        Ok(())
+    }
+
+    fn end_elem(&mut self, name: QualName) -> io::Result<()> {
+        let ignore_children = self.stack.pop().is_some_and(|info| info.ignore_children);
+        if ignore_children {
+            return Ok(());
+        }
+        write!(self.writer, "</{}>", name.local)
+    }
+
+    fn write_text(&mut self, text: &str) -> io::Result<()> {
+        let raw = self
+            .parent()
+            .and_then(|p| p.html_name.as_deref())
+            .is_some_and(|local| RAW_TEXT.contains(&local) || local == "noscript");
+        if raw {
+            self.writer.write_all(text.as_bytes())
+        } else {
+            self.write_escaped(text, false)
+        }
+    }
+
+    fn write_comment(&mut self, text: &str) -> io::Result<()> {
+        write!(self.writer, "<!--{text}-->")
+    }
+
+    fn write_doctype(&mut self, name: &str) -> io::Result<()> {
+        write!(self.writer, "<!DOCTYPE {name}>")
+    }
+
+    fn write_processing_instruction(&mut self, target: &str, data: &str) -> io::Result<()> {
+        write!(self.writer, "<?{target} {data}>")
+    }
+}
@@ -0,0 +1,56 @@
+// Message lookup of `Options::messages`. Evaluates to a function taking the render locale, an
+// array collecting keys without a message and the catalogs, most specific first, and returning
+// the `$t` and `$tc` helpers.
+((locale, missing, ...catalogs) => {
+    // Message at a dotted key path, from the first catalog that has it
+    const lookup = (key) => {
+        for (const catalog of catalogs) {
+            let message = catalog[key];
+            if (message === undefined) {
+                message = String(key)
+                    .split(".")
+                    .reduce((value, part) => (value !== null && typeof value === "object" ? value[part] : undefined), catalog);
+            }
+            if (message !== undefined && message !== null) {
+                return message;
+            }
+        }
+        missing.push(String(key));
+        return undefined;
+    };
+
+    // Replace `{name}` and `{0}` placeholders with named or list parameters
+    const format = (message, params) =>
+        String(message).replace(/\{\s*(\w+)\s*\}/g, (match, name) =>
+            params !== null && typeof params === "object" && name in params ? String(params[name]) : match,
+        );
+
+    const $t = (key, params) => {
+        const message = lookup(key);
+        return typeof message === "string" ? format(message, params) : String(key);
+    };
+
+    // Plural message for `count`: either `one | many` and `zero | one | many` choices separated
+    // by `|`, like vue-i18n, or an object of CLDR plural categories
+    const $tc = (key, count = 1, params) => {
+        const message = lookup(key);
+        const values = { count, n: count, ...params };
+
+        if (typeof message === "string") {
+            const choices = message.split("|").map((choice) => choice.trim());
+            const n = Math.abs(count);
+            const index = choices.length >= 3 ? (n === 0 ? 0 : n === 1 ? 1 : 2) : n === 1 ? 0 : 1;
+            return format(choices[Math.min(index, choices.length - 1)], values);
+        }
+        if (message !== null && typeof message === "object") {
+            const category = new Intl.PluralRules(locale).select(count);
+            const choice = message[count === 0 && "zero" in message ? "zero" : category] ?? message.other;
+            if (choice !== undefined) {
+                return format(choice, values);
+            }
+        }
+        return String(key);
+    };
+
+    return { $t, $tc };
+})
@@ -1,8 +1,9 @@
-use boa_engine::{JsValue, JsVariant, property::PropertyKey};
+use boa_engine::{JsObject, JsString, JsValue};
 use html5ever::{
     QualName,
     driver::ParseOpts,
-    parse_document, serialize,
+    local_name, ns, parse_document,
+    serialize::TraversalScope,
     tendril::{StrTendril, TendrilSink},
 };
 use markup5ever_rcdom::{Handle, Node, NodeData, RcDom, SerializableHandle};
@@ -11,21 +12,84 @@ use serde::Serialize;
 use std::cell::RefCell;
 use std::rc::{Rc, Weak};
 use std::str::FromStr;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
+use std::{fmt, io};
 
+mod component;
+mod css;
+mod data;
 mod engine;
+mod error;
+mod escape;
+mod format;
+mod loader;
+mod mustache;
+mod options;
+mod renderer;
+mod sink;
+mod site;
+mod state;
+mod stream;
+mod warning;
+mod writer;
+
+pub use component::{Component, Style};
+pub use data::DataFormat;
 use engine::Engine;
+pub use error::TemplateError;
+use escape::TextContext;
+pub use loader::Loader;
+pub use options::{Options, Whitespace};
+pub use renderer::Renderer;
+use sink::{LineSink, Lines};
+pub use site::{Build, Site};
+pub use state::{InitialState, StateLocation};
+pub use stream::RenderStream;
+pub use warning::{Warning, WarningKind};
 
-static SYNTAX_MUSTACHE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(?s)\{\{\s*(.+?)\s*\}\}").unwrap());
 static SYNTAX_BIND: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^(?:v-bind:|:)(?<arg>.+)$").unwrap());
-static SYNTAX_FOR: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(
-        r"^\s*(?<val>[\p{XID_Start}_$]\p{XID_Continue}*)\s*(?:,\s*(?<key>[\p{XID_Start}_$]\p{XID_Continue}*)\s*(?:,\s*(?<idx>[\p{XID_Start}_$]\p{XID_Continue}*)\s*)?)?\s+(?:in|of)\s+(?<iter>.+)\s*$",
-    )
-    .unwrap()
-});
+static SYNTAX_FOR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)^\s*(?<alias>.*?)\s+(?:in|of)\s+(?<iter>.*?)\s*$").unwrap());
+static SYNTAX_TAG_NAME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-zA-Z][a-zA-Z0-9-]*$").unwrap());
+static SYNTAX_FOR_ITERATOR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r",(?<key>[^,\}\]]*)(?:,(?<idx>[^,\}\]]*))?$").unwrap());
+
+// Nesting of components and includes before the render fails, to stop runaway recursion
+const MAX_DEPTH: usize = 64;
+
+// Attributes that are present or absent rather than holding a value
+const BOOLEAN_ATTRIBUTES: &[&str] = &[
+    "allowfullscreen",
+    "async",
+    "autofocus",
+    "autoplay",
+    "checked",
+    "controls",
+    "default",
+    "defer",
+    "disabled",
+    "formnovalidate",
+    "hidden",
+    "inert",
+    "ismap",
+    "itemscope",
+    "loop",
+    "multiple",
+    "muted",
+    "nomodule",
+    "novalidate",
+    "open",
+    "readonly",
+    "required",
+    "reversed",
+    "scoped",
+    "seamless",
+    "selected",
+];
+// Attributes that take "true" or "false"
+const ENUMERATED_ATTRIBUTES: &[&str] = &["contenteditable", "draggable", "spellcheck"];
 
 /// Render HTML template with data
 ///
@@ -41,21 +105,267 @@ static SYNTAX_FOR: LazyLock<Regex> = LazyLock::new(|| {
 /// assert!(result.contains("Hello"));
 /// ```
 pub fn render(html: String, data: impl Serialize) -> Result<String, anyhow::Error> {
-    let dom = parse_document(RcDom::default(), ParseOpts::default())
+    Options::default().render(html, data)
+}
+
+/// Render HTML template with data, along with the warnings found while rendering
+///
+/// Warnings report problems that don't stop the render but are likely bugs, such as an orphan
+/// `v-else` or an expression that failed to evaluate. See [`WarningKind`] for the categories.
+///
+/// # Examples
+///
+/// ```
+/// use prevue::{WarningKind, render_with_warnings};
+/// use serde_json::json;
+///
+/// let html = r#"<p>{{ missing.name }}</p>"#;
+/// let (result, warnings) = render_with_warnings(html.to_string(), json!({})).unwrap();
+/// assert!(result.contains("<p></p>"));
+/// assert_eq!(warnings.len(), 1);
+/// assert_eq!(warnings[0].kind, WarningKind::EvalError);
+/// ```
+pub fn render_with_warnings(
+    html: String,
+    data: impl Serialize,
+) -> Result<(String, Vec<Warning>), anyhow::Error> {
+    Options::default().render_with_warnings(html, data)
+}
+
+/// Render HTML template with data into an [`io::Write`] sink
+///
+/// Output is streamed to the writer as it is serialized instead of being collected
+/// into a `String` first.
+///
+/// # Examples
+///
+/// ```
+/// use prevue::render_to_writer;
+/// use serde_json::json;
+///
+/// let html = r#"<p>{{ message }}</p>"#;
+/// let mut output = Vec::new();
+/// render_to_writer(html.to_string(), json!({ "message": "Hello" }), &mut output).unwrap();
+/// assert!(String::from_utf8(output).unwrap().contains("<p>Hello</p>"));
+/// ```
+pub fn render_to_writer(
+    html: String,
+    data: impl Serialize,
+    writer: &mut impl io::Write,
+) -> Result<(), anyhow::Error> {
+    Options::default().render_to_writer(html, data, writer)
+}
+
+/// Render HTML template with data into a [`fmt::Write`] sink
+///
+/// # Examples
+///
+/// ```
+/// use prevue::render_to_fmt;
+/// use serde_json::json;
+///
+/// let html = r#"<p>{{ message }}</p>"#;
+/// let mut output = String::new();
+/// render_to_fmt(html.to_string(), json!({ "message": "Hello" }), &mut output).unwrap();
+/// assert!(output.contains("<p>Hello</p>"));
+/// ```
+pub fn render_to_fmt(
+    html: String,
+    data: impl Serialize,
+    writer: &mut impl fmt::Write,
+) -> Result<(), anyhow::Error> {
+    Options::default().render_to_fmt(html, data, writer)
+}
+
+/// Render HTML template with data as a stream of chunks
+///
+/// Each top-level section of the body is rendered and yielded as soon as it is complete,
+/// so the beginning of a large document can be sent before the rest has been rendered.
+///
+/// # Examples
+///
+/// ```
+/// use prevue::render_stream;
+/// use serde_json::json;
+///
+/// let html = r#"<h1>{{ title }}</h1><p v-for="item in list">{{ item }}</p>"#;
+/// let data = json!({ "title": "Report", "list": [1, 2] });
+/// let chunks: Vec<String> = render_stream(html.to_string(), data)
+///     .unwrap()
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(chunks.first().unwrap(), "<html><head></head><body>");
+/// assert_eq!(chunks.concat(), "<html><head></head><body><h1>Report</h1><p>1</p><p>2</p></body></html>");
+/// ```
+pub fn render_stream(html: String, data: impl Serialize) -> Result<RenderStream, anyhow::Error> {
+    Options::default().render_stream(html, data)
+}
+
+// Parse HTML template into a DOM, along with the source line of its nodes and warnings for
+// parse errors that change the template
+fn parse(html: &str) -> Result<(RcDom, Lines, Vec<Warning>), anyhow::Error> {
+    parse_from(html, 1)
+}
+
+// Parse HTML template that starts at `first_line` of its source file
+fn parse_from(
+    html: &str,
+    first_line: usize,
+) -> Result<(RcDom, Lines, Vec<Warning>), anyhow::Error> {
+    let parsed = parse_document(LineSink::starting_at(first_line), ParseOpts::default())
         .from_utf8()
         .read_from(&mut html.as_bytes())?;
-    let mut engine = Engine::new(data);
-    traverse(&Rc::clone(&dom.document), &mut engine);
-
-    let mut buffer = Vec::new();
-    serialize(
-        &mut buffer,
-        &SerializableHandle::from(Rc::clone(&dom.document)),
-        Default::default(),
+    Ok(parsed)
+}
+
+// Process directives and bindings of a parsed document in place, returning the warnings
+fn render_document(
+    document: &Handle,
+    lines: Lines,
+    data: impl Serialize,
+    options: &Options,
+) -> Result<Vec<Warning>, anyhow::Error> {
+    let state = state_element(&data, options)?;
+    let mut engine = Engine::new(data, lines, options)?;
+    traverse(document, &mut engine);
+    if let Some(error) = engine.error.take() {
+        return Err(error);
+    }
+    if options.inject_styles && !engine.styles.is_empty() {
+        append_style(document, &engine.styles.concat(), options.nonce.as_deref());
+    }
+    if let Some((location, element)) = state {
+        insert_state(document, location, element);
+    }
+    if options.fragment {
+        keep_body(document);
+    }
+    format::format(document, options.whitespace);
+    Ok(engine.warnings)
+}
+
+// Add a `<style>` element with the style sheet to the head of a document
+fn append_style(document: &Handle, css: &str, nonce: Option<&str>) {
+    let Some(head) =
+        stream::find_element(document, "html").and_then(|html| stream::find_element(&html, "head"))
+    else {
+        return;
+    };
+
+    // `</` would end the raw text early
+    let style = create_raw_text_element(local_name!("style"), &css.replace("</", "<\\/"), nonce);
+    style.parent.set(Some(Rc::downgrade(&head)));
+    head.children.borrow_mut().push(style);
+}
+
+// Make the children of `<head>` and `<body>` the children of the document. The parser moves
+// leading `<script>`, `<style>` and `<template>` elements to the head, so they are kept in
+// document order before the body content, as in `render_include`
+fn keep_body(document: &Handle) {
+    let Some(html) = stream::find_element(document, "html") else {
+        return;
+    };
+    let mut children = Vec::new();
+    for section in ["head", "body"] {
+        if let Some(section) = stream::find_element(&html, section) {
+            children.extend(section.children.take());
+        }
+    }
+    for child in &children {
+        child.parent.set(Some(Rc::downgrade(document)));
+    }
+    *document.children.borrow_mut() = children;
+}
+
+// Initial state script of the options with the render data, and where it goes
+fn state_element(
+    data: &impl Serialize,
+    options: &Options,
+) -> Result<Option<(StateLocation, Handle)>, anyhow::Error> {
+    let Some(state) = &options.initial_state else {
+        return Ok(None);
+    };
+    let nonce = state.nonce.as_deref().or(options.nonce.as_deref());
+    let script = create_raw_text_element(local_name!("script"), &state.script(data)?, nonce);
+    Ok(Some((state.location, script)))
+}
+
+// Add the initial state script at its location
+fn insert_state(document: &Handle, location: StateLocation, element: Handle) {
+    let Some(html) = stream::find_element(document, "html") else {
+        return;
+    };
+    let parent = match location {
+        StateLocation::Head => stream::find_element(&html, "head"),
+        StateLocation::BodyStart | StateLocation::BodyEnd => stream::find_element(&html, "body"),
+    };
+    let Some(parent) = parent else {
+        return;
+    };
+
+    element.parent.set(Some(Rc::downgrade(&parent)));
+    let mut children = parent.children.borrow_mut();
+    match location {
+        StateLocation::BodyStart => children.insert(0, element),
+        StateLocation::Head | StateLocation::BodyEnd => children.push(element),
+    }
+}
+
+// `<script>` or `<style>` element holding the given text
+fn create_raw_text_element(name: html5ever::LocalName, text: &str, nonce: Option<&str>) -> Handle {
+    let element = Node::new(NodeData::Element {
+        name: QualName::new(None, ns!(html), name),
+        attrs: RefCell::new(Vec::new()),
+        template_contents: RefCell::new(None),
+        mathml_annotation_xml_integration_point: false,
+    });
+    if let Some(nonce) = nonce {
+        add_nonce(&element, nonce);
+    }
+    let text = create_text_node(text);
+    text.parent.set(Some(Rc::downgrade(&element)));
+    element.children.borrow_mut().push(text);
+    element
+}
+
+// Serialize a rendered document into a writer
+fn write_document(
+    document: &Handle,
+    writer: impl io::Write,
+    options: &Options,
+) -> Result<(), anyhow::Error> {
+    format::serialize(
+        writer,
+        &SerializableHandle::from(Rc::clone(document)),
+        TraversalScope::ChildrenOnly(None),
+        options.whitespace,
     )?;
+    Ok(())
+}
 
-    let rendered = String::from_utf8(buffer)?;
-    Ok(rendered)
+// Add the `nonce` attribute to a `<script>` or `<style>` element that has none
+fn add_nonce(element: &Handle, nonce: &str) {
+    let NodeData::Element { name, attrs, .. } = &element.data else {
+        return;
+    };
+    if !matches!(name.local.as_ref(), "script" | "style") {
+        return;
+    }
+    let mut attrs = attrs.borrow_mut();
+    if attrs.iter().any(|attr| attr.name.local.as_ref() == "nonce") {
+        return;
+    }
+    attrs.push(html5ever::Attribute {
+        name: QualName::new(None, ns!(), html5ever::LocalName::from("nonce")),
+        value: StrTendril::from_str(nonce).unwrap(),
+    });
+}
+
+fn add_nonce_in_subtree(node: &Handle, nonce: &str) {
+    add_nonce(node, nonce);
+    for child in get_children_source(node) {
+        add_nonce_in_subtree(&child, nonce);
+    }
 }
 
 // Traverse and process a node
@@ -68,26 +378,54 @@ fn traverse(handle: &Handle, engine: &mut Engine) {
     let mut if_chain_hit = false;
 
     for node in children.iter() {
-        if let NodeData::Element { attrs, .. } = &node.data
-            && find_and_remove_directive(attrs, "v-pre").is_some()
-        {
-            continue;
+        traverse_child(node, engine, &mut in_if_chain, &mut if_chain_hit);
+    }
+}
+
+// Process a child node in place, replacing it in its parent when directives apply
+fn traverse_child(
+    node: &Handle,
+    engine: &mut Engine,
+    in_if_chain: &mut bool,
+    if_chain_hit: &mut bool,
+) {
+    if let NodeData::Element { attrs, .. } = &node.data
+        && find_and_remove_directive(attrs, "v-pre").is_some()
+    {
+        // Scripts skip compilation, not the Content-Security-Policy
+        if let Some(nonce) = &engine.nonce {
+            add_nonce_in_subtree(node, nonce);
         }
+        return;
+    }
 
-        let processed = process_directives(node, engine, &mut in_if_chain, &mut if_chain_hit);
+    let processed = process_directives(node, engine, in_if_chain, if_chain_hit);
 
-        if let Some(replacements) = processed {
-            replace_in_children_source(node, &replacements);
-        } else {
+    if let Some(replacements) = processed {
+        replace_in_children_source(node, &replacements);
+    } else if let Some(rendered) = resolve_element(node, engine) {
+        replace_in_children_source(node, &rendered);
+    } else {
+        traverse(node, engine);
+    }
+}
+
+// Process a node that has no structural directives left, returning what it renders to
+fn render_node(node: &Handle, engine: &mut Engine) -> Vec<Handle> {
+    match resolve_element(node, engine) {
+        Some(rendered) => rendered,
+        None => {
             traverse(node, engine);
+            vec![Rc::clone(node)]
         }
     }
 }
 
 // Hydrate node: process v-bind and mustache
 fn hydrate_node(handle: &Handle, engine: &mut Engine) {
+    engine.at(handle);
     match &handle.data {
-        NodeData::Element { attrs, .. } => {
+        NodeData::Element { name, attrs, .. } => {
             let mut renames: Vec<(usize, String, String)> = Vec::new();
             let mut removals: Vec<usize> = Vec::new();
             let mut additions: Vec<(String, QualName, String)> = Vec::new();
@@ -95,6 +433,16 @@ fn hydrate_node(handle: &Handle, engine: &mut Engine) {
             for (i, attr) in attrs.borrow().iter().enumerate() {
                 let name_ref: &str = attr.name.local.as_ref();
 
+                // `key` and `ref` are virtual DOM hints, not HTML attributes
+                let bind_arg = SYNTAX_BIND
+                    .captures(name_ref)
+                    .and_then(|caps| caps.name("arg"))
+                    .map_or(name_ref, |arg| arg.as_str());
+                if is_reserved_attribute(bind_arg) {
+                    removals.push(i);
+                    continue;
+                }
+
                 if name_ref == "v-text" {
                     if let Some(value) = engine.eval_str(attr.value.as_ref()) {
                         let has_following_text_sibling = {
@@ -151,20 +499,23 @@ fn hydrate_node(handle: &Handle, engine: &mut Engine) {
                 // v-bind object syntax: v-bind="{ key: value }"
                 if name_ref == "v-bind" {
                     if let Ok(js_val) = engine.eval(attr.value.as_ref())
-                        && let Ok(Some(json_val)) = js_val.to_json(&mut engine.context)
-                        && let Some(obj) = json_val.as_object()
+                        && js_val.is_object()
+                        && let Ok(entries) = engine.entries(js_val)
                     {
-                        for (key, val) in obj.iter() {
-                            if val.is_null() {
+                        for (key, val) in entries {
+                            if is_reserved_attribute(&key) {
                                 continue;
                             }
-                            let value_str = val
-                                .as_str()
-                                .map(str::to_string)
-                                .unwrap_or_else(|| val.to_string());
-                            additions.push((key.clone(), attr.name.clone(), value_str));
+                            if let Some(value_str) = bound_attribute(&key, val, engine) {
+                                additions.push((key, attr.name.clone(), value_str));
+                            }
                         }
                         removals.push(i);
+                    } else {
+                        engine.warn(
+                            WarningKind::InvalidBindObject,
+                            format!("`v-bind=\"{}\"` is not an object", attr.value.trim()),
+                        );
                     }
                     continue;
                 }
@@ -180,9 +531,14 @@ fn hydrate_node(handle: &Handle, engine: &mut Engine) {
                             continue;
                         }
                         let inner = &arg_raw[1..arg_raw.len() - 1];
-                        match (engine.eval_fmt(inner), engine.eval_fmt(value_expr)) {
-                            (Some(resolved), Some(value)) => renames.push((i, resolved, value)),
-                            _ => removals.push(i),
+                        let bound = engine.eval_fmt(inner).and_then(|resolved| {
+                            let value = engine.eval(value_expr).ok()?;
+                            let value = bound_attribute(&resolved, value, engine)?;
+                            Some((resolved, value))
+                        });
+                        match bound {
+                            Some((resolved, value)) => renames.push((i, resolved, value)),
+                            None => removals.push(i),
                         }
                     } else {
                         let target = if value_expr.is_empty() {
@@ -190,7 +546,11 @@ fn hydrate_node(handle: &Handle, engine: &mut Engine) {
                         } else {
                             value_expr
                         };
-                        match engine.eval_fmt(target) {
+                        let bound = engine
+                            .eval(target)
+                            .ok()
+                            .and_then(|value| bound_attribute(arg_raw, value, engine));
+                        match bound {
                             Some(value) => renames.push((i, arg_raw.to_string(), value)),
                             None => removals.push(i),
                         }
@@ -227,15 +587,59 @@ fn hydrate_node(handle: &Handle, engine: &mut Engine) {
                     });
                 }
             }
+
+            // Elements of a component with scoped styles carry its attribute, except the
+            // document elements the parser adds around the template
+            if let Some(scope_id) = &engine.scope_id
+                && !matches!(name.local.as_ref(), "html" | "head" | "body")
+            {
+                let mut attrs_mut = attrs.borrow_mut();
+                if !attrs_mut.iter().any(|a| a.name.local.as_ref() == scope_id) {
+                    attrs_mut.push(html5ever::Attribute {
+                        name: QualName::new(
+                            None,
+                            ns!(),
+                            html5ever::LocalName::from(scope_id.as_str()),
+                        ),
+                        value: StrTendril::new(),
+                    });
+                }
+            }
+
+            if let Some(nonce) = &engine.nonce {
+                add_nonce(handle, nonce);
+            }
         }
         NodeData::Text { contents } => {
             let mut content = contents.borrow_mut();
-            let replacements: Vec<(std::ops::Range<usize>, String)> = SYNTAX_MUSTACHE
-                .captures_iter(&content)
-                .filter_map(|capture| {
-                    let range = capture.get(0)?.range();
-                    let expr = capture.get(1)?.as_str();
-                    Some((range, engine.eval_fmt(expr).unwrap_or_default()))
+            let interpolations = match mustache::tokenize(
+                &content,
+                &engine.delimiters.open,
+                &engine.delimiters.close,
+            ) {
+                Ok(interpolations) => interpolations,
+                Err(offset) => {
+                    let line = engine
+                        .lines
+                        .get(handle)
+                        .map(|line| line + content[..offset].matches('\n').count());
+                    let source = content[offset..].lines().next().unwrap_or_default();
+                    engine.fail(TemplateError::new(
+                        line,
+                        format!("unterminated interpolation `{}`", source.trim_end()),
+                    ));
+                    return;
+                }
+            };
+            let context = escape::text_context(handle);
+            let line = engine.lines.get(handle);
+            let replacements: Vec<(std::ops::Range<usize>, String)> = interpolations
+                .into_iter()
+                .map(|interpolation| {
+                    let newlines = content[..interpolation.range.start].matches('\n').count();
+                    engine.line = line.map(|line| line + newlines);
+                    let evaluated = interpolate(interpolation.expr, context, engine);
+                    (interpolation.range, evaluated)
                 })
                 .collect();
 
@@ -251,6 +655,110 @@ fn hydrate_node(handle: &Handle, engine: &mut Engine) {
     }
 }
 
+// Value of a bound attribute following Vue's rules; `None` removes the attribute.
+// Boolean attributes are present only when truthy, enumerated ones are "true" or "false".
+fn bound_attribute(name: &str, value: JsValue, engine: &mut Engine) -> Option<String> {
+    let name = name.to_ascii_lowercase();
+
+    if BOOLEAN_ATTRIBUTES.contains(&name.as_str()) {
+        return (value.to_boolean() || value.is_string()).then(String::new);
+    }
+
+    if ENUMERATED_ATTRIBUTES.contains(&name.as_str()) {
+        let is_false = value.as_boolean() == Some(false);
+        return Some(match engine.fmt(value) {
+            None => "false".to_string(),
+            Some(value) if is_false || value == "false" => "false".to_string(),
+            Some(value)
+                if name == "contenteditable"
+                    && matches!(
+                        value.as_str(),
+                        "events" | "caret" | "typing" | "plaintext-only"
+                    ) =>
+            {
+                value
+            }
+            Some(_) => "true".to_string(),
+        });
+    }
+
+    if name == "style" && value.is_object() {
+        return style_declarations(value, engine);
+    }
+
+    let value = engine.fmt(value)?;
+    if escape::is_url_attribute(&name) {
+        return Some(escape::url(value));
+    }
+    Some(value)
+}
+
+// Declarations of an object or array `:style` binding, with CSS-escaped values
+fn style_declarations(value: JsValue, engine: &mut Engine) -> Option<String> {
+    let items = if value.as_object().is_some_and(|obj| obj.is_array()) {
+        engine.items(&value).ok()?
+    } else {
+        vec![value]
+    };
+
+    let mut declarations = String::new();
+    for item in items {
+        if item.is_string() {
+            let style = engine.fmt(item).unwrap_or_default();
+            let style = style.trim().trim_end_matches(';');
+            if !style.is_empty() {
+                declarations.push_str(style);
+                declarations.push(';');
+            }
+            continue;
+        }
+        if !item.is_object() {
+            continue;
+        }
+        for (key, val) in engine.entries(item).ok()? {
+            if !val.is_string() && !val.is_number() {
+                continue;
+            }
+            let val = engine.fmt(val).unwrap_or_default();
+            declarations.push_str(&format!("{}:{};", hyphenate(&key), escape::css(&val)));
+        }
+    }
+    Some(declarations)
+}
+
+// Style property name in kebab case; custom properties are kept as they are
+fn hyphenate(key: &str) -> String {
+    if key.starts_with("--") {
+        return key.to_string();
+    }
+    let mut hyphenated = String::with_capacity(key.len() + 2);
+    for (i, c) in key.chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            hyphenated.push('-');
+        }
+        hyphenated.push(c.to_ascii_lowercase());
+    }
+    hyphenated
+}
+
+fn is_reserved_attribute(name: &str) -> bool {
+    matches!(name, "key" | "ref" | "ref_for" | "ref_key")
+}
+
+// Evaluate an interpolation and escape it for where it is written
+fn interpolate(expr: &str, context: TextContext, engine: &mut Engine) -> String {
+    match context {
+        TextContext::Html => engine.eval_fmt(expr).unwrap_or_default(),
+        TextContext::Style => escape::css(&engine.eval_fmt(expr).unwrap_or_default()),
+        TextContext::Script => engine
+            .eval(expr)
+            .ok()
+            .and_then(|value| value.to_json(&mut engine.context).ok())
+            .map(|json| escape::json(&json.unwrap_or_default()))
+            .unwrap_or_default(),
+    }
+}
+
 // Replace node with new_nodes in its parent's children
 fn replace_in_children_source(node: &Handle, new_nodes: &[Handle]) {
     let Some(node_parent_weak) = node.parent.take() else {
@@ -341,13 +849,29 @@ fn process_directives(
     let directive_else = find_and_remove_directive(attrs, "v-else");
     let directive_for = find_and_remove_directive(attrs, "v-for");
 
-    // Helper to expand and traverse targets
+    engine.at(node);
+    if directive_for.is_some()
+        && (directive_if.is_some() || directive_elif.is_some() || directive_else.is_some())
+    {
+        engine.warn(
+            WarningKind::IfWithFor,
+            "`v-if` and `v-for` on the same element; `v-if` is evaluated first",
+        );
+    }
+
+    // Helper to expand and traverse targets. Like Vue 3, v-if takes precedence over
+    // v-for on the same element, so the loop only runs once the condition holds.
     let render_targets = |node: &Handle, engine: &mut Engine| {
-        let targets = expand_targets(node);
-        for target in &targets {
-            traverse(target, engine);
+        if let Some(expr) = &directive_for {
+            return process_for(node, engine, expr).unwrap_or_default();
         }
-        targets
+        let targets = expand_targets(node, engine);
+        let wrap = engine.hydration && is_template(node) && needs_fragment(&targets);
+        let mut rendered = Vec::new();
+        for target in targets {
+            rendered.extend(render_node(&target, engine));
+        }
+        if wrap { fragment(rendered) } else { rendered }
     };
 
     // v-if
@@ -357,13 +881,17 @@ fn process_directives(
         return Some(if *if_chain_hit {
             render_targets(node, engine)
         } else {
-            Vec::new()
+            skipped_branch(node, engine)
         });
     }
 
     // v-else-if
     if let Some(expr) = directive_elif {
         if !*in_if_chain {
+            engine.warn(
+                WarningKind::OrphanElse,
+                "`v-else-if` has no preceding `v-if`; the element is kept",
+            );
             return None;
         }
         if *if_chain_hit {
@@ -373,13 +901,17 @@ fn process_directives(
         return Some(if *if_chain_hit {
             render_targets(node, engine)
         } else {
-            Vec::new()
+            skipped_branch(node, engine)
         });
     }
 
     // v-else
     if directive_else.is_some() {
         if !*in_if_chain {
+            engine.warn(
+                WarningKind::OrphanElse,
+                "`v-else` has no preceding `v-if`; the element is kept",
+            );
             return None;
         }
         *in_if_chain = false;
@@ -394,114 +926,596 @@ fn process_directives(
     *in_if_chain = false;
 
     // v-for
-    directive_for.map(|expr| process_for(node, engine, &expr).unwrap_or_default())
+    directive_for
+        .as_deref()
+        .map(|expr| process_for(node, engine, expr).unwrap_or_default())
 }
 
-// Process for directive
-fn process_for(node: &Handle, engine: &mut Engine, expr: &str) -> Option<Vec<Handle>> {
-    let syntax = SYNTAX_FOR.captures(expr)?;
+// Nothing for a branch of a `v-if` chain that isn't rendered, or the `<!--v-if-->` placeholder
+// Vue hydrates when it is the last branch and none was rendered
+fn skipped_branch(node: &Handle, engine: &Engine) -> Vec<Handle> {
+    if engine.hydration && !continues_if_chain(node) {
+        vec![create_comment_node("v-if")]
+    } else {
+        Vec::new()
+    }
+}
 
-    let iter_iden = syntax.name("iter")?;
-    let iter_expr = iter_iden.as_str().trim();
-    let iter_wrapped = if iter_expr.starts_with('{') {
-        format!("({})", iter_expr)
+// Whether the next element sibling, past whitespace and comments, is a `v-else-if` or `v-else`
+fn continues_if_chain(node: &Handle) -> bool {
+    let Some(parent) = node.parent.take() else {
+        return false;
+    };
+    node.parent.set(Some(Weak::clone(&parent)));
+    let Some(parent) = parent.upgrade() else {
+        return false;
+    };
+    let children = parent.children.borrow();
+    children
+        .iter()
+        .skip_while(|child| !Rc::ptr_eq(child, node))
+        .skip(1)
+        .find(|child| {
+            !is_whitespace_text_node(child) && !matches!(child.data, NodeData::Comment { .. })
+        })
+        .is_some_and(|next| match &next.data {
+            NodeData::Element { attrs, .. } => attrs
+                .borrow()
+                .iter()
+                .any(|attr| matches!(attr.name.local.as_ref(), "v-else-if" | "v-else")),
+            _ => false,
+        })
+}
+
+// Render `<component :is>` and `<include>` elements; `None` for other nodes
+fn resolve_element(node: &Handle, engine: &mut Engine) -> Option<Vec<Handle>> {
+    let NodeData::Element { name, attrs, .. } = &node.data else {
+        return None;
+    };
+    if name.local.as_ref() == "include" {
+        return Some(render_include(node, engine));
+    }
+    if name.local.as_ref() != "component" {
+        return None;
+    }
+    let position = attrs
+        .borrow()
+        .iter()
+        .position(|attr| matches!(attr.name.local.as_ref(), "is" | ":is" | "v-bind:is"))?;
+    let is = attrs.borrow_mut().remove(position);
+
+    engine.at(node);
+    let target = if is.name.local.as_ref() == "is" {
+        Some(is.value.to_string())
     } else {
-        iter_expr.to_string()
+        match engine.eval(is.value.trim()) {
+            Ok(value) => engine.fmt(value),
+            Err(_) => return Some(Vec::new()),
+        }
+    };
+    // Like Vue, a missing component renders nothing
+    let Some(target) = target.filter(|target| !target.is_empty()) else {
+        return Some(Vec::new());
     };
 
-    let val_iden = syntax.name("val")?;
-    let indent_opt = get_indent(node);
-    let mut result_nodes = Vec::new();
+    let components = Arc::clone(&engine.components);
+    let registered = components
+        .get_key_value(target.as_str())
+        .or_else(|| components.get_key_value(pascalize(&target).as_str()));
+    if let Some((name, component)) = registered {
+        return Some(render_registered(node, name, component, engine));
+    }
+    if SYNTAX_TAG_NAME.is_match(&target) {
+        return Some(render_as_tag(node, &target.to_ascii_lowercase(), engine));
+    }
 
-    match engine.eval(iter_wrapped.as_str()).map(|val| val.variant()) {
-        Ok(JsVariant::Object(obj)) if obj.is_array() => {
-            let keys = obj.own_property_keys(&mut engine.context).ok()?;
+    engine.warn(
+        WarningKind::UnknownComponent,
+        format!("`<component>` is `{target}`, which is not a registered component or a tag name"),
+    );
+    Some(Vec::new())
+}
 
-            for property_key in keys.iter() {
-                let PropertyKey::Index(index) = property_key else {
-                    continue;
-                };
-                if engine.enter_scope().is_err() {
-                    continue;
-                }
+// Render a registered component in place of `<component>`: its attributes are the props and
+// its children fill the component's `<slot>`
+fn render_registered(
+    node: &Handle,
+    name: &str,
+    component: &Component,
+    engine: &mut Engine,
+) -> Vec<Handle> {
+    if engine.depth >= MAX_DEPTH {
+        engine.fail(TemplateError::new(
+            engine.line,
+            format!("component `{name}` is nested more than {MAX_DEPTH} levels deep"),
+        ));
+        return Vec::new();
+    }
+    let NodeData::Element { attrs, .. } = &node.data else {
+        return Vec::new();
+    };
+    let props = component_props(attrs, engine);
 
-                let item = obj
-                    .get(property_key.clone(), &mut engine.context)
-                    .unwrap_or(JsValue::undefined());
-                engine.set_val(val_iden.as_str(), item);
+    // Slot content renders in the scope of the template using the component
+    let slot = Node::new(NodeData::Document);
+    for child in node.children.take() {
+        child.parent.set(Some(Rc::downgrade(&slot)));
+        slot.children.borrow_mut().push(child);
+    }
+    traverse(&slot, engine);
 
-                if let Some(key_iden) = syntax.name("key") {
-                    engine.set_val(key_iden.as_str(), JsValue::new(index.get()));
-                }
+    let (dom, lines, warnings) = match parse_from(component.template(), component.template_line()) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            engine.fail(err);
+            return Vec::new();
+        }
+    };
+    engine.warnings.extend(warnings);
+    let Some(body) = stream::find_element(&dom.document, "html")
+        .and_then(|html| stream::find_element(&html, "body"))
+    else {
+        return Vec::new();
+    };
 
-                process_for_iteration(node, engine, &indent_opt, &mut result_nodes);
+    let scope_id = component.scope_id().map(str::to_string);
+    let frame = engine.enter_template(lines, None, scope_id, true);
+    if let Err(err) = engine.enter_component(props, component.script()) {
+        engine.exit_template(frame);
+        engine.fail(anyhow::anyhow!("component `{name}`: {err}"));
+        return Vec::new();
+    }
+    engine.depth += 1;
+    traverse(&body, engine);
+    engine.depth -= 1;
+    engine.exit_template(frame);
+
+    let css = component.css();
+    if !css.is_empty() && !engine.styles.contains(&css) {
+        engine.styles.push(css);
+    }
 
-                engine.exit_scope();
+    fill_slots(&body, &slot, engine.hydration);
+    let mut rendered = body.children.take();
+    // Whitespace around the component's root nodes belongs to its file
+    while rendered.first().is_some_and(is_whitespace_text_node) {
+        rendered.remove(0);
+    }
+    while rendered.last().is_some_and(is_whitespace_text_node) {
+        rendered.pop();
+    }
+    for node in &rendered {
+        node.parent.take();
+    }
+    let roots = rendered
+        .iter()
+        .filter(|node| !is_whitespace_text_node(node));
+    if engine.hydration && roots.count() > 1 {
+        return fragment(rendered);
+    }
+    rendered
+}
+
+// Inline the template named by `<include src>`. It sees the current scopes, and the other
+// attributes as variables; with `only`, it sees those attributes alone. Children of the element,
+// like the siblings a self-closed `<include />` swallows, are kept after the included content.
+fn render_include(node: &Handle, engine: &mut Engine) -> Vec<Handle> {
+    let NodeData::Element { attrs, .. } = &node.data else {
+        return Vec::new();
+    };
+    engine.at(node);
+    let isolated = find_and_remove_directive(attrs, "only").is_some();
+    let src = ["src", ":src", "v-bind:src"]
+        .into_iter()
+        .find_map(|name| find_and_remove_directive(attrs, name).map(|value| (name, value)));
+    let name = match src {
+        Some(("src", value)) => Some(value),
+        Some((_, expr)) => engine.eval(&expr).ok().and_then(|value| engine.fmt(value)),
+        None => None,
+    };
+    let Some(name) = name.filter(|name| !name.is_empty()) else {
+        engine.fail(TemplateError::new(engine.line, "`<include>` has no `src`"));
+        return Vec::new();
+    };
+
+    if engine.includes.contains(&name) {
+        let mut cycle = engine.includes.clone();
+        cycle.push(name);
+        engine.fail(TemplateError::new(
+            engine.line,
+            format!("include cycle: {}", cycle.join(" -> ")),
+        ));
+        return Vec::new();
+    }
+    if engine.depth >= MAX_DEPTH {
+        engine.fail(TemplateError::new(
+            engine.line,
+            format!("include `{name}` is nested more than {MAX_DEPTH} levels deep"),
+        ));
+        return Vec::new();
+    }
+    let source = match engine.loader.load(&name) {
+        Ok(source) => source,
+        Err(err) => {
+            engine.fail(TemplateError::new(engine.line, err.to_string()));
+            return Vec::new();
+        }
+    };
+    let (dom, lines, warnings) = match parse(&source) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            engine.fail(err);
+            return Vec::new();
+        }
+    };
+    engine
+        .warnings
+        .extend(warnings.into_iter().map(|warning| Warning {
+            file: Some(name.clone()),
+            ..warning
+        }));
+
+    let bindings = component_props(attrs, engine);
+    let scope_id = engine.scope_id.clone();
+    let frame = engine.enter_template(lines, Some(name.clone()), scope_id, isolated);
+    let entered = if isolated {
+        engine.enter_component(bindings, None)
+    } else {
+        engine.enter_scope().map(|()| {
+            for (name, value) in bindings {
+                engine.set_val(&name, value);
+            }
+        })
+    };
+    let mut rendered = Vec::new();
+    if entered.is_ok()
+        && let Some(html) = stream::find_element(&dom.document, "html")
+    {
+        engine.includes.push(name);
+        engine.depth += 1;
+        // Elements the parser moves to the head, like `<title>`, stay in place
+        for section in ["head", "body"] {
+            if let Some(section) = stream::find_element(&html, section) {
+                traverse(&section, engine);
+                rendered.extend(section.children.take());
             }
         }
-        Ok(JsVariant::Object(obj)) => {
-            let property_keys = obj.own_property_keys(&mut engine.context).ok()?;
-
-            for (idx, property_key) in property_keys.iter().enumerate() {
-                if engine.enter_scope().is_err() {
-                    continue;
-                }
+        engine.depth -= 1;
+        engine.includes.pop();
+    }
+    engine.exit_template(frame);
 
-                let value = obj
-                    .get(property_key.clone(), &mut engine.context)
-                    .unwrap_or(JsValue::undefined());
-                engine.set_val(val_iden.as_str(), value);
+    // Whitespace around the included nodes belongs to its file
+    while rendered.first().is_some_and(is_whitespace_text_node) {
+        rendered.remove(0);
+    }
+    while rendered.last().is_some_and(is_whitespace_text_node) {
+        rendered.pop();
+    }
 
-                if let Some(key_iden) = syntax.name("key") {
-                    engine.set_val(key_iden.as_str(), property_key.into());
-                }
-                if let Some(idx_iden) = syntax.name("idx") {
-                    engine.set_val(idx_iden.as_str(), JsValue::new(idx as i32));
-                }
+    let children = Node::new(NodeData::Document);
+    for child in node.children.take() {
+        child.parent.set(Some(Rc::downgrade(&children)));
+        children.children.borrow_mut().push(child);
+    }
+    traverse(&children, engine);
+    rendered.extend(children.children.take());
 
-                process_for_iteration(node, engine, &indent_opt, &mut result_nodes);
+    for node in &rendered {
+        node.parent.take();
+    }
+    rendered
+}
 
-                engine.exit_scope();
+// Props of a component from the attributes of `<component>`: static values are strings and
+// bound ones are evaluated in the current scope
+fn component_props(
+    attrs: &RefCell<Vec<html5ever::Attribute>>,
+    engine: &mut Engine,
+) -> Vec<(String, JsValue)> {
+    let mut props = Vec::new();
+    for attr in attrs.borrow().iter() {
+        let name = attr.name.local.as_ref();
+        if name == "v-bind" {
+            if let Ok(value) = engine.eval(attr.value.as_ref())
+                && value.is_object()
+                && let Ok(entries) = engine.entries(value)
+            {
+                props.extend(
+                    entries
+                        .into_iter()
+                        .map(|(key, value)| (camelize(&key), value)),
+                );
+            } else {
+                engine.warn(
+                    WarningKind::InvalidBindObject,
+                    format!("`v-bind=\"{}\"` is not an object", attr.value.trim()),
+                );
+            }
+            continue;
+        }
+        if let Some(arg) = SYNTAX_BIND.captures(name).and_then(|caps| caps.name("arg")) {
+            let arg = arg.as_str();
+            let expr = match attr.value.trim() {
+                "" => arg,
+                expr => expr,
+            };
+            if !arg.starts_with('[')
+                && let Ok(value) = engine.eval(expr)
+            {
+                props.push((camelize(arg), value));
             }
+            continue;
         }
-        Ok(JsVariant::Integer32(val)) => {
-            for (idx, num) in (1..=val).enumerate() {
-                if engine.enter_scope().is_err() {
-                    continue;
-                }
+        if name.starts_with("v-") || name.starts_with('@') || is_reserved_attribute(name) {
+            continue;
+        }
+        let value = JsValue::from(JsString::from(attr.value.as_ref()));
+        props.push((camelize(name), value));
+    }
+    props
+}
 
-                engine.set_val(val_iden.as_str(), JsValue::new(num));
+// Render `<component>` as a plain element with the given tag
+fn render_as_tag(node: &Handle, tag: &str, engine: &mut Engine) -> Vec<Handle> {
+    let NodeData::Element { attrs, .. } = &node.data else {
+        return Vec::new();
+    };
+    let element = Node::new(NodeData::Element {
+        name: QualName::new(None, ns!(html), html5ever::LocalName::from(tag)),
+        attrs: RefCell::new(attrs.take()),
+        template_contents: RefCell::new(None),
+        mathml_annotation_xml_integration_point: false,
+    });
+    for child in node.children.take() {
+        child.parent.set(Some(Rc::downgrade(&element)));
+        element.children.borrow_mut().push(child);
+    }
+    if let Some(line) = engine.lines.get(node) {
+        engine.lines.set(&element, line);
+    }
+    traverse(&element, engine);
+    vec![element]
+}
 
-                if let Some(key_iden) = syntax.name("key") {
-                    engine.set_val(key_iden.as_str(), JsValue::new(idx));
-                }
+// Replace the `<slot>` elements of a rendered component with the slot content, or with their
+// fallback children when there is none. Named slots always get their fallback, and for
+// hydration either is a fragment.
+fn fill_slots(node: &Handle, content: &Handle, hydration: bool) {
+    let has_content = content
+        .children
+        .borrow()
+        .iter()
+        .any(|child| !is_whitespace_text_node(child));
+    let children = node.children.borrow().clone();
+    for child in children {
+        let NodeData::Element { name, attrs, .. } = &child.data else {
+            continue;
+        };
+        if name.local.as_ref() != "slot" {
+            fill_slots(&child, content, hydration);
+            continue;
+        }
+        let named = attrs
+            .borrow()
+            .iter()
+            .any(|attr| attr.name.local.as_ref() == "name" && attr.value.as_ref() != "default");
+        let replacement: Vec<Handle> = if has_content && !named {
+            content.children.borrow().iter().map(clone_node).collect()
+        } else {
+            child.children.borrow().clone()
+        };
+        let replacement = if hydration {
+            fragment(replacement)
+        } else {
+            replacement
+        };
+        replace_in_children_source(&child, &replacement);
+    }
+}
+
+// Prop name of a kebab-case attribute, like `fooBar` for `foo-bar`
+fn camelize(name: &str) -> String {
+    let mut camelized = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '-' {
+            upper = true;
+        } else if upper {
+            camelized.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            camelized.push(c);
+        }
+    }
+    camelized
+}
+
+// Component name of a kebab-case tag, like `MyCard` for `my-card`
+fn pascalize(name: &str) -> String {
+    let camelized = camelize(name);
+    let mut chars = camelized.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+// Process for directive
+fn process_for(node: &Handle, engine: &mut Engine, expr: &str) -> Option<Vec<Handle>> {
+    let syntax = SYNTAX_FOR.captures(expr)?;
+    let iter_expr = syntax.name("iter")?.as_str();
+
+    // Split `(value, key, index)` aliases the way Vue does; the value may be a pattern
+    let alias = syntax.name("alias")?.as_str().trim();
+    let alias = alias.strip_prefix('(').unwrap_or(alias);
+    let alias = alias.strip_suffix(')').unwrap_or(alias);
+    let (val_alias, key_alias, idx_alias) = match SYNTAX_FOR_ITERATOR.captures(alias) {
+        Some(iterator) => (
+            &alias[..iterator.get(0)?.start()],
+            iterator.name("key").map(|m| m.as_str()),
+            iterator.name("idx").map(|m| m.as_str()),
+        ),
+        None => (alias, None, None),
+    };
+    let aliases = [Some(val_alias), key_alias, idx_alias]
+        .into_iter()
+        .map(|alias| match alias.map(str::trim) {
+            Some(alias) if !alias.is_empty() => ForAlias::new(alias, engine).map(Some),
+            _ => Some(None),
+        })
+        .collect::<Option<Vec<_>>>()?;
 
-                process_for_iteration(node, engine, &indent_opt, &mut result_nodes);
+    let source = engine.eval(iter_expr).ok()?;
+    let entries = engine.iterate(source).ok()?;
 
-                engine.exit_scope();
+    let indent_opt = get_indent(node);
+    let mut result_nodes = Vec::new();
+
+    for entry in entries {
+        if engine.enter_scope().is_err() {
+            continue;
+        }
+
+        for (alias, value) in aliases.iter().zip(entry) {
+            if let Some(alias) = alias {
+                alias.bind(engine, value);
             }
         }
-        Ok(JsVariant::String(val)) => {
-            for (idx, ch) in val.to_std_string_escaped().chars().enumerate() {
-                if engine.enter_scope().is_err() {
-                    continue;
-                }
 
-                engine.set_val(val_iden.as_str(), JsValue::new(ch));
+        process_for_iteration(node, engine, &indent_opt, &mut result_nodes);
 
-                if let Some(key_iden) = syntax.name("key") {
-                    engine.set_val(key_iden.as_str(), JsValue::new(idx));
-                }
+        engine.exit_scope();
+    }
 
-                process_for_iteration(node, engine, &indent_opt, &mut result_nodes);
+    Some(if engine.hydration {
+        fragment(result_nodes)
+    } else {
+        result_nodes
+    })
+}
 
-                engine.exit_scope();
+// Alias of a v-for entry: a plain identifier or a destructuring pattern
+struct ForAlias {
+    names: Vec<String>,
+    // `(pattern) => [names]`, for patterns only
+    destructure: Option<JsObject>,
+}
+
+impl ForAlias {
+    fn new(alias: &str, engine: &mut Engine) -> Option<Self> {
+        if is_identifier(alias) {
+            return Some(Self {
+                names: vec![alias.to_string()],
+                destructure: None,
+            });
+        }
+
+        let names = bound_names(alias);
+        if names.is_empty() {
+            return None;
+        }
+        let destructure = engine
+            .eval(&format!("({alias}) => [{}]", names.join(", ")))
+            .ok()?
+            .as_object()?;
+        Some(Self {
+            names,
+            destructure: Some(destructure),
+        })
+    }
+
+    // Bind the alias names in the current scope
+    fn bind(&self, engine: &mut Engine, value: JsValue) {
+        let Some(destructure) = &self.destructure else {
+            engine.set_val(&self.names[0], value);
+            return;
+        };
+
+        let Ok(values) = engine
+            .call(destructure, &[value])
+            .and_then(|values| engine.items(&values))
+        else {
+            return;
+        };
+        for (name, value) in self.names.iter().zip(values) {
+            engine.set_val(name, value);
+        }
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(is_identifier_start) && chars.all(is_identifier_part)
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
+fn is_identifier_part(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+// Names bound by a destructuring pattern, e.g. `a`, `b` and `rest` for `{ a, x: [b = 1], ...rest }`.
+// Property keys and default values are skipped.
+fn bound_names(pattern: &str) -> Vec<String> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut names = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '=' => {
+                // Default value: skip to the next element of the pattern
+                let mut depth = 0;
+                i += 1;
+                while i < chars.len() {
+                    match chars[i] {
+                        '(' | '[' | '{' => depth += 1,
+                        ')' | ']' | '}' if depth == 0 => break,
+                        ')' | ']' | '}' => depth -= 1,
+                        ',' if depth == 0 => break,
+                        quote @ ('"' | '\'' | '`') => i = skip_quoted(&chars, i, quote),
+                        _ => (),
+                    }
+                    i += 1;
+                }
+                continue;
+            }
+            quote @ ('"' | '\'' | '`') => i = skip_quoted(&chars, i, quote),
+            c if is_identifier_start(c) => {
+                let start = i;
+                while i < chars.len() && is_identifier_part(chars[i]) {
+                    i += 1;
+                }
+                let is_key = chars[i..]
+                    .iter()
+                    .find(|c| !c.is_whitespace())
+                    .is_some_and(|c| *c == ':');
+                if !is_key {
+                    names.push(chars[start..i].iter().collect());
+                }
+                continue;
             }
+            _ => (),
         }
-        _ => {}
+        i += 1;
     }
 
-    Some(result_nodes)
+    names
+}
+
+// Position of the closing quote of a string starting at `start`
+fn skip_quoted(chars: &[char], start: usize, quote: char) -> usize {
+    let mut i = start + 1;
+    while i < chars.len() && chars[i] != quote {
+        if chars[i] == '\\' {
+            i += 1;
+        }
+        i += 1;
+    }
+    i
 }
 
 // Process a single iteration of v-for
@@ -511,10 +1525,11 @@ fn process_for_iteration(
     indent_opt: &Option<String>,
     result_nodes: &mut Vec<Handle>,
 ) {
-    let targets = expand_targets(node);
+    let targets = expand_targets(node, engine);
     if targets.is_empty() {
         return;
     }
+    let wrap = engine.hydration && is_template(node) && needs_fragment(&targets);
 
     let mut iteration_nodes = Vec::new();
     for (target_idx, target) in targets.into_iter().enumerate() {
@@ -542,12 +1557,14 @@ fn process_for_iteration(
                 {
                     iteration_nodes.push(create_text_node(indent));
                 }
-                traverse(&target, engine);
-                iteration_nodes.push(target);
+                iteration_nodes.extend(render_node(&target, engine));
             }
         }
     }
 
+    if wrap {
+        iteration_nodes = fragment(iteration_nodes);
+    }
     if !iteration_nodes.is_empty() {
         if !result_nodes.is_empty()
             && let Some(indent) = indent_opt
@@ -558,7 +1575,7 @@ fn process_for_iteration(
     }
 }
 
-fn expand_targets(node: &Handle) -> Vec<Handle> {
+fn expand_targets(node: &Handle, engine: &mut Engine) -> Vec<Handle> {
     if let NodeData::Element {
         template_contents, ..
     } = &node.data
@@ -585,6 +1602,7 @@ fn expand_targets(node: &Handle) -> Vec<Handle> {
             .filter(|c| !is_whitespace_text_node(c))
             .map(|c| {
                 let cloned = clone_node(c);
+                engine.lines.copy(c, &cloned);
                 cloned.parent.take();
                 if indent_adjustment != 0 {
                     adjust_indent_in_subtree(&cloned, indent_adjustment);
@@ -595,6 +1613,7 @@ fn expand_targets(node: &Handle) -> Vec<Handle> {
     }
 
     let cloned = clone_node(node);
+    engine.lines.copy(node, &cloned);
     cloned.parent.take();
     vec![cloned]
 }
@@ -747,6 +1766,31 @@ fn create_text_node(text: &str) -> Handle {
     })
 }
 
+fn create_comment_node(text: &str) -> Handle {
+    Node::new(NodeData::Comment {
+        contents: StrTendril::from_str(text).unwrap(),
+    })
+}
+
+// Nodes between the `<!--[-->` and `<!--]-->` markers of a Vue fragment
+fn fragment(nodes: Vec<Handle>) -> Vec<Handle> {
+    let mut wrapped = Vec::with_capacity(nodes.len() + 2);
+    wrapped.push(create_comment_node("["));
+    wrapped.extend(nodes);
+    wrapped.push(create_comment_node("]"));
+    wrapped
+}
+
+// Like Vue, `<template>` contents other than a single element render as a fragment
+fn needs_fragment(targets: &[Handle]) -> bool {
+    !matches!(targets, [target] if matches!(target.data, NodeData::Element { .. }))
+}
+
+fn is_template(node: &Handle) -> bool {
+    matches!(&node.data, NodeData::Element { template_contents, .. }
+        if template_contents.borrow().is_some())
+}
+
 fn is_whitespace_text_node(node: &Handle) -> bool {
     if let NodeData::Text { contents } = &node.data {
         contents.borrow().chars().all(|c| c.is_whitespace())
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Source of the named templates `<include>` inlines
+///
+/// Templates added with [`template`](Self::template) are found first, then files under the
+/// directories added with [`dir`](Self::dir), in order. Names are relative paths that can't
+/// leave those directories.
+///
+/// # Examples
+///
+/// ```
+/// use prevue::{Loader, Options};
+/// use serde_json::json;
+///
+/// let loader = Loader::new().template("greeting.html", "<p>Hello, {{ name }}!</p>");
+/// let html = r#"<main><include src="greeting.html"></include></main>"#;
+/// let result = Options::new()
+///     .loader(loader)
+///     .render(html.to_string(), json!({ "name": "World" }))
+///     .unwrap();
+/// assert!(result.contains("<main><p>Hello, World!</p></main>"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Loader {
+    templates: HashMap<String, String>,
+    dirs: Vec<PathBuf>,
+}
+
+impl Loader {
+    /// Create a loader without templates
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a template under a name
+    pub fn template(mut self, name: impl Into<String>, source: impl Into<String>) -> Self {
+        self.templates.insert(name.into(), source.into());
+        self
+    }
+
+    /// Add a directory to look templates up in
+    pub fn dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.dirs.push(path.into());
+        self
+    }
+
+    /// Source of a template
+    pub fn load(&self, name: &str) -> Result<String, anyhow::Error> {
+        if let Some(source) = self.templates.get(name) {
+            return Ok(source.clone());
+        }
+
+        let relative = Path::new(name);
+        if !is_relative_inside(relative) {
+            anyhow::bail!(
+                "template name `{name}` must be a relative path inside the template directories"
+            );
+        }
+        for dir in &self.dirs {
+            let path = dir.join(relative);
+            if path.is_file() {
+                return Ok(fs::read_to_string(path)?);
+            }
+        }
+        anyhow::bail!("template `{name}` not found")
+    }
+}
+
+// Whether a path stays inside the directory it is joined to: no root, prefix or `..`
+pub(crate) fn is_relative_inside(path: &Path) -> bool {
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
@@ -0,0 +1,106 @@
+use std::ops::Range;
+
+// Text interpolation found in a text node
+pub(crate) struct Interpolation<'a> {
+    pub range: Range<usize>,
+    pub expr: &'a str,
+}
+
+// Split text into interpolations between `open` and `close` delimiters.
+// Fails with the byte offset of the opening delimiter of an unterminated interpolation.
+pub(crate) fn tokenize<'a>(
+    text: &'a str,
+    open: &str,
+    close: &str,
+) -> Result<Vec<Interpolation<'a>>, usize> {
+    let mut interpolations = Vec::new();
+    let mut cursor = 0;
+
+    while let Some(found) = text[cursor..].find(open) {
+        let start = cursor + found;
+        let expr_start = start + open.len();
+        let expr_end =
+            scan_expression(text.as_bytes(), expr_start, close.as_bytes()).ok_or(start)?;
+        let end = expr_end + close.len();
+
+        interpolations.push(Interpolation {
+            range: start..end,
+            expr: text[expr_start..expr_end].trim(),
+        });
+        cursor = end;
+    }
+
+    Ok(interpolations)
+}
+
+// Find the closing delimiter of a JS expression starting at `start`, skipping string literals,
+// template literals, comments and anything nested in brackets
+fn scan_expression(bytes: &[u8], start: usize, close: &[u8]) -> Option<usize> {
+    // b'{' for an open bracket or `${` substitution, b'`' for an open template literal
+    let mut stack: Vec<u8> = Vec::new();
+    let mut i = start;
+
+    while i < bytes.len() {
+        if stack.last() == Some(&b'`') {
+            match bytes[i] {
+                b'\\' => i += 1,
+                b'`' => {
+                    stack.pop();
+                }
+                b'$' if bytes.get(i + 1) == Some(&b'{') => {
+                    stack.push(b'{');
+                    i += 1;
+                }
+                _ => (),
+            }
+            i += 1;
+            continue;
+        }
+
+        if stack.is_empty() && bytes[i..].starts_with(close) {
+            return Some(i);
+        }
+
+        match bytes[i] {
+            quote @ (b'\'' | b'"') => {
+                i = skip_string(bytes, i + 1, quote)?;
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = bytes[i..]
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .map_or(bytes.len(), |p| i + p);
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = bytes[i + 2..]
+                    .windows(2)
+                    .position(|w| w == b"*/")
+                    .map(|p| i + 2 + p + 2)?;
+                continue;
+            }
+            b'`' => stack.push(b'`'),
+            b'{' | b'(' | b'[' => stack.push(b'{'),
+            b'}' | b')' | b']' => {
+                stack.pop();
+            }
+            _ => (),
+        }
+        i += 1;
+    }
+
+    None
+}
+
+// Skip a quoted string literal; returns the position after its closing quote
+fn skip_string(bytes: &[u8], mut i: usize, quote: u8) -> Option<usize> {
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b if b == quote => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+    None
+}
@@ -0,0 +1,630 @@
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::SystemTime;
+use std::{fmt, io};
+
+use crate::component;
+use crate::engine::Engine;
+use crate::writer::FmtWriter;
+use crate::{
+    Component, InitialState, Loader, RenderStream, Warning, parse, parse_from, render_document,
+    state_element, write_document,
+};
+
+/// Whitespace handling of the rendered output
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Whitespace {
+    /// Keep the source whitespace, adjusting indentation of removed and repeated nodes
+    #[default]
+    Preserve,
+    /// Vue's `whitespace: 'condense'` rule: drop whitespace between elements when it contains
+    /// a newline and collapse other whitespace runs into a single space
+    Condense,
+    /// Condense whitespace, remove comments and drop optional attribute quotes
+    Minify,
+    /// Re-indent the whole document with two spaces per level
+    Pretty,
+}
+
+/// Render options
+///
+/// # Examples
+///
+/// ```
+/// use prevue::{Options, Whitespace};
+/// use serde_json::json;
+///
+/// let html = r#"
+///     <ul>
+///         <li v-for="item in list">{{ item }}</li>
+///     </ul>
+/// "#;
+/// let options = Options::new().whitespace(Whitespace::Minify);
+/// let result = options.render(html.to_string(), json!({ "list": [1, 2] })).unwrap();
+/// assert_eq!(result, "<html><head></head><body><ul><li>1</li><li>2</li></ul></body></html>");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    pub(crate) whitespace: Whitespace,
+    pub(crate) fragment: bool,
+    pub(crate) delimiters: Delimiters,
+    pub(crate) sandbox: bool,
+    pub(crate) prelude: bool,
+    pub(crate) now: Option<SystemTime>,
+    pub(crate) seed: Option<u64>,
+    pub(crate) locale: Option<String>,
+    pub(crate) time_zone: Option<String>,
+    // Message catalogs by locale, shared between clones
+    pub(crate) messages: Arc<HashMap<String, JsonValue>>,
+    pub(crate) fallback_locale: Option<String>,
+    // Component definition object installed on the data scope
+    pub(crate) script: Option<String>,
+    // `data-v-` attribute and style sheet of the component being rendered
+    pub(crate) scope_id: Option<String>,
+    pub(crate) css: Option<String>,
+    pub(crate) inject_styles: bool,
+    pub(crate) hydration: bool,
+    pub(crate) initial_state: Option<InitialState>,
+    pub(crate) nonce: Option<String>,
+    // Components `<component :is>` can render, shared between clones
+    pub(crate) components: Arc<HashMap<String, Component>>,
+    pub(crate) loader: Arc<Loader>,
+    // Name of the template being rendered, when it was loaded by name
+    pub(crate) file: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Delimiters {
+    pub open: String,
+    pub close: String,
+}
+
+impl Default for Delimiters {
+    fn default() -> Self {
+        Self {
+            open: "{{".to_string(),
+            close: "}}".to_string(),
+        }
+    }
+}
+
+impl Options {
+    /// Create default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whitespace handling of the output
+    pub fn whitespace(mut self, whitespace: Whitespace) -> Self {
+        self.whitespace = whitespace;
+        self
+    }
+
+    /// Write the contents of `<body>` alone, for templates of page fragments
+    ///
+    /// The rest of the document is dropped. Leading `<script>`, `<style>` and `<template>`
+    /// elements, which the parser moves to `<head>`, are kept before the body content, along
+    /// with styles and scripts added to `<head>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use prevue::Options;
+    /// use serde_json::json;
+    ///
+    /// let html = r#"<li v-for="item in list">{{ item }}</li>"#;
+    /// let options = Options::new().fragment(true);
+    /// let result = options.render(html.to_string(), json!({ "list": [1, 2] })).unwrap();
+    /// assert_eq!(result, "<li>1</li><li>2</li>");
+    /// ```
+    pub fn fragment(mut self, fragment: bool) -> Self {
+        self.fragment = fragment;
+        self
+    }
+
+    /// Set the text interpolation delimiters, like Vue's `delimiters` option
+    ///
+    /// Defaults to `{{` and `}}`. Text inside `v-pre` elements is left untouched either way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use prevue::Options;
+    /// use serde_json::json;
+    ///
+    /// let html = r#"<p>${ message } {{ kept }}</p>"#;
+    /// let options = Options::new().delimiters("${", "}");
+    /// let result = options.render(html.to_string(), json!({ "message": "Hello" })).unwrap();
+    /// assert!(result.contains("<p>Hello {{ kept }}</p>"));
+    /// ```
+    pub fn delimiters(mut self, open: impl Into<String>, close: impl Into<String>) -> Self {
+        self.delimiters = Delimiters {
+            open: open.into(),
+            close: close.into(),
+        };
+        self
+    }
+
+    /// Evaluate expressions in a hardened JavaScript realm
+    ///
+    /// Intrinsics are frozen and the `eval` and `Function` constructors are removed, so
+    /// expressions can only reach the data and `Math`, `Date`, `JSON`, `Number`, `String`,
+    /// `Array`, `Object`, `Intl` and a few other safe globals. Bindings must be single
+    /// expressions, names missing from the data read as `undefined`, and data is frozen so one
+    /// scope can't change what another one sees.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use prevue::Options;
+    /// use serde_json::json;
+    ///
+    /// let html = r#"<p>{{ typeof eval }} {{ Math.max(...list) }}</p>"#;
+    /// let options = Options::new().sandbox(true);
+    /// let result = options.render(html.to_string(), json!({ "list": [1, 2] })).unwrap();
+    /// assert!(result.contains("<p>undefined 2</p>"));
+    /// ```
+    pub fn sandbox(mut self, sandbox: bool) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// Install formatting helpers, callable as plain functions in any binding
+    ///
+    /// | Helper | Result |
+    /// |---|---|
+    /// | `formatDate(date, format = "%Y-%m-%d", { utc })` | strftime directives (`%Y %y %m %d %e %H %I %M %S %L %p %B %b %A %a`), or ISO 8601 with `"iso"` |
+    /// | `formatNumber(value, digits)` | Locale grouping, with exactly `digits` fraction digits when given |
+    /// | `formatCurrency(value, currency = "USD")` | Locale currency format |
+    /// | `formatBytes(bytes, digits = 1)` | Binary multiples, like `1.5 KB` |
+    /// | `timeAgo(date, now = Date.now())` | Relative time, like `3 days ago` or `in 2 hours` |
+    /// | `truncate(text, length = 100, suffix = "…")` | Text cut to `length` characters |
+    /// | `slugify(text)` | Lowercase ASCII words joined by dashes |
+    /// | `pluralize(count, singular, plural = singular + "s")` | Word form for `count` |
+    ///
+    /// Data with the same name takes precedence over a helper.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use prevue::Options;
+    /// use serde_json::json;
+    ///
+    /// let html = r#"<p>{{ count }} {{ pluralize(count, "file") }}, {{ formatBytes(size) }}</p>"#;
+    /// let options = Options::new().prelude(true);
+    /// let result = options
+    ///     .render(html.to_string(), json!({ "count": 2, "size": 1536 }))
+    ///     .unwrap();
+    /// assert!(result.contains("<p>2 files, 1.5 KB</p>"));
+    /// ```
+    pub fn prelude(mut self, prelude: bool) -> Self {
+        self.prelude = prelude;
+        self
+    }
+
+    /// Pin the clock of `Date` and `Date.now()` to a fixed instant
+    ///
+    /// `new Date()` with arguments is unaffected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use prevue::Options;
+    /// use serde_json::json;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let html = r#"<p>{{ new Date().toISOString() }}</p>"#;
+    /// let options = Options::new().now(UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+    /// let result = options.render(html.to_string(), json!({})).unwrap();
+    /// assert!(result.contains("<p>2023-11-14T22:13:20.000Z</p>"));
+    /// ```
+    pub fn now(mut self, now: SystemTime) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    /// Seed `Math.random`, making its sequence the same on every render
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Set the default locale of number `toLocaleString`, `localeCompare` and `Intl` formatters,
+    /// and the locale of [`messages`](Self::messages)
+    ///
+    /// Locales passed by the template take precedence. The JavaScript engine doesn't format
+    /// dates yet: `Date` `toLocale*String` methods and `Intl.DateTimeFormat` don't work.
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Set the local time zone of `Date`, read by `getHours()`, `toString()` and the other local
+    /// time methods
+    ///
+    /// Takes `"UTC"` or a fixed offset like `"+09:00"`, as the JavaScript engine has no time zone
+    /// database; rendering fails on other names. The offset is also the default `timeZone` of
+    /// `Date` `toLocale*String` methods and `Intl.DateTimeFormat`, for when the engine supports
+    /// them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use prevue::Options;
+    /// use serde_json::json;
+    ///
+    /// let html = r#"<p>{{ new Date(0).getHours() }}</p>"#;
+    /// let options = Options::new().time_zone("+09:00");
+    /// let result = options.render(html.to_string(), json!({})).unwrap();
+    /// assert!(result.contains("<p>9</p>"));
+    /// ```
+    pub fn time_zone(mut self, time_zone: impl Into<String>) -> Self {
+        self.time_zone = Some(time_zone.into());
+        self
+    }
+
+    /// Add the JSON message catalog of a locale, used by the `$t` and `$tc` helpers
+    ///
+    /// Messages are looked up by key, either flat or as a dotted path into nested objects, in the
+    /// catalog of the [`locale`](Self::locale), then of its language (`pt` for `pt-BR`), then of
+    /// the [`fallback_locale`](Self::fallback_locale). A missing message renders as its key.
+    ///
+    /// - `$t(key, params)` replaces `{name}` placeholders with named parameters, or `{0}` with
+    ///   list parameters.
+    /// - `$tc(key, count, params)` picks a plural form: `"one | many"` or `"zero | one | many"`
+    ///   choices like vue-i18n, or an object of CLDR plural categories (`one`, `few`, `other`, ...).
+    ///   `{count}` and `{n}` are replaced with the count.
+    ///
+    /// Catalogs are shared between clones of the options, so choosing the locale of each render
+    /// with `options.clone().locale(..)` is cheap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use prevue::Options;
+    /// use serde_json::json;
+    ///
+    /// let html = r#"<p>{{ $t("greeting", { name }) }}, {{ $tc("apples", 3) }}</p>"#;
+    /// let options = Options::new()
+    ///     .messages("en", json!({ "greeting": "Hello {name}", "apples": "one apple | {count} apples" }))
+    ///     .messages("fr", json!({ "greeting": "Bonjour {name}" }))
+    ///     .fallback_locale("en");
+    ///
+    /// let result = options.clone().locale("fr").render(html.to_string(), json!({ "name": "Léa" })).unwrap();
+    /// assert!(result.contains("<p>Bonjour Léa, 3 apples</p>"));
+    /// ```
+    pub fn messages(mut self, locale: impl Into<String>, catalog: JsonValue) -> Self {
+        Arc::make_mut(&mut self.messages).insert(locale.into(), catalog);
+        self
+    }
+
+    /// Set the locale whose messages are used when the render locale lacks one
+    ///
+    /// Also the message locale when no [`locale`](Self::locale) is set.
+    pub fn fallback_locale(mut self, locale: impl Into<String>) -> Self {
+        self.fallback_locale = Some(locale.into());
+        self
+    }
+
+    // Locale of the messages, defaulting to English
+    pub(crate) fn message_locale(&self) -> &str {
+        self.locale
+            .as_deref()
+            .or(self.fallback_locale.as_deref())
+            .unwrap_or("en")
+    }
+
+    // Catalogs to look messages up in, most specific first
+    pub(crate) fn message_catalogs(&self) -> Vec<&JsonValue> {
+        let mut locales: Vec<&str> = Vec::new();
+        for locale in [Some(self.message_locale()), self.fallback_locale.as_deref()]
+            .into_iter()
+            .flatten()
+        {
+            let language = locale.split(['-', '_']).next().unwrap_or(locale);
+            for locale in [locale, language] {
+                if !locales.contains(&locale) {
+                    locales.push(locale);
+                }
+            }
+        }
+        locales
+            .into_iter()
+            .filter_map(|locale| self.messages.get(locale))
+            .collect()
+    }
+
+    /// Define computed properties and methods for the template, like a component `<script>`
+    ///
+    /// The script is a definition object, optionally after `export default`, with `computed`
+    /// getters, `methods`, `data()` and `props` defaults. Each getter runs once per render, on
+    /// first access, and getters and methods run with `this` bound to the data. Names in the
+    /// render data take precedence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use prevue::Options;
+    /// use serde_json::json;
+    ///
+    /// let html = r#"<p>{{ fullName }}: {{ price(quantity) }}</p>"#;
+    /// let options = Options::new().script(
+    ///     r#"{
+    ///         computed: {
+    ///             fullName() { return `${this.first} ${this.last}`; },
+    ///         },
+    ///         methods: {
+    ///             price(count) { return (count * this.unit).toFixed(2); },
+    ///         },
+    ///     }"#,
+    /// );
+    /// let data = json!({ "first": "Ada", "last": "Lovelace", "unit": 2.5, "quantity": 3 });
+    /// let result = options.render(html.to_string(), data).unwrap();
+    /// assert!(result.contains("<p>Ada Lovelace: 7.50</p>"));
+    /// ```
+    pub fn script(mut self, script: impl AsRef<str>) -> Self {
+        self.script = Some(component::expression(script.as_ref()).to_string());
+        self
+    }
+
+    /// Register a component `<component :is="name">` can render
+    ///
+    /// The element's attributes and bindings become the component's props, with kebab-case
+    /// names camelized, and its children are rendered in place of the component's `<slot>`.
+    /// A kebab-case `:is` value also finds a PascalCase name. Values that aren't registered
+    /// render the element as that tag instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use prevue::{Component, Options};
+    /// use serde_json::json;
+    ///
+    /// let badge = Component::parse(r#"<template><b :title="label"><slot></slot></b></template>"#).unwrap();
+    /// let html = r#"<component :is="kind" label="New">{{ count }}</component>"#;
+    /// let options = Options::new().component("Badge", badge);
+    ///
+    /// let result = options.render(html.to_string(), json!({ "kind": "badge", "count": 3 })).unwrap();
+    /// assert!(result.contains(r#"<b title="New">3</b>"#));
+    ///
+    /// let result = options.render(html.to_string(), json!({ "kind": "em", "count": 3 })).unwrap();
+    /// assert!(result.contains(r#"<em label="New">3</em>"#));
+    /// ```
+    pub fn component(mut self, name: impl Into<String>, component: Component) -> Self {
+        Arc::make_mut(&mut self.components).insert(name.into(), component);
+        self
+    }
+
+    /// Set the source of the templates `<include src="name">` inlines
+    ///
+    /// The included template sees the variables of the current scope, including `v-for`
+    /// aliases, and the element's other attributes and bindings. With the `only` attribute, it
+    /// sees those attributes alone. Include cycles fail the render, and errors in an included
+    /// template carry its name in [`TemplateError::file`](crate::TemplateError::file).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use prevue::{Loader, Options};
+    /// use serde_json::json;
+    ///
+    /// let loader = Loader::new().template("item.html", "<li>{{ prefix }}{{ item }}</li>");
+    /// let html = r##"<ul><include v-for="item in list" src="item.html" prefix="#"></include></ul>"##;
+    /// let options = Options::new().loader(loader);
+    ///
+    /// let result = options.render(html.to_string(), json!({ "list": [1, 2] })).unwrap();
+    /// assert!(result.contains("<ul><li>#1</li><li>#2</li></ul>"));
+    /// ```
+    pub fn loader(mut self, loader: Loader) -> Self {
+        self.loader = Arc::new(loader);
+        self
+    }
+
+    /// Add the style sheets of rendered components to `<head>` as a `<style>` element
+    ///
+    /// Without it, a component's style sheet is available from [`Component::css`] for a
+    /// separate file or the page layout. [`render_stream`](Self::render_stream) sends `<head>`
+    /// before the components render, so it fails with this option.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use prevue::{Component, Options};
+    /// use serde_json::json;
+    ///
+    /// let source = "<template><p>Hi</p></template><style scoped>p { color: red; }</style>";
+    /// let component = Component::parse(source).unwrap();
+    /// let scope = component.scope_id().unwrap();
+    ///
+    /// let result = Options::new().inject_styles(true).render_component(&component, json!({})).unwrap();
+    /// assert!(result.starts_with(&format!("<html><head><style>p[{scope}] {{ color: red; }}\n</style></head>")));
+    /// ```
+    pub fn inject_styles(mut self, inject: bool) -> Self {
+        self.inject_styles = inject;
+        self
+    }
+
+    /// Mark fragments and removed branches the way Vue's server renderer does, so the client
+    /// runtime can hydrate the output
+    ///
+    /// `v-for` lists, `<template>` contents that aren't a single element, components with
+    /// several root nodes and slot contents are wrapped in `<!--[-->` and `<!--]-->`, and a
+    /// `v-if` chain that renders nothing leaves a `<!--v-if-->` placeholder. The client template
+    /// should be compiled with the same whitespace handling, which is
+    /// [`Whitespace::Condense`] for Vue's default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use prevue::{Options, Whitespace};
+    /// use serde_json::json;
+    ///
+    /// let html = r#"<ul><li v-for="item in list">{{ item }}</li></ul><p v-if="list.length > 2">More</p>"#;
+    /// let options = Options::new().hydration(true).whitespace(Whitespace::Minify);
+    /// let result = options.render(html.to_string(), json!({ "list": [1, 2] })).unwrap();
+    /// assert_eq!(
+    ///     result,
+    ///     "<html><head></head><body><ul><!--[--><li>1</li><li>2</li><!--]--></ul><!--v-if--></body></html>"
+    /// );
+    /// ```
+    pub fn hydration(mut self, hydration: bool) -> Self {
+        self.hydration = hydration;
+        self
+    }
+
+    /// Embed the render data in the output, for the client to hydrate with
+    ///
+    /// See [`InitialState`].
+    pub fn initial_state(mut self, state: InitialState) -> Self {
+        self.initial_state = Some(state);
+        self
+    }
+
+    /// Set the Content-Security-Policy nonce of the request
+    ///
+    /// Every `<script>` and `<style>` element of the output gets a `nonce` attribute, including
+    /// injected styles, the [initial state](Self::initial_state) script and elements under
+    /// `v-pre`. Elements with a `nonce` of their own keep it. Templates read the nonce as
+    /// `$nonce`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use prevue::Options;
+    /// use serde_json::json;
+    ///
+    /// let html = r#"<script>start()</script><link rel="preload" as="script" :nonce="$nonce">"#;
+    /// let options = Options::new().nonce("r4nd0m");
+    /// let result = options.render(html.to_string(), json!({})).unwrap();
+    /// assert!(result.contains(r#"<script nonce="r4nd0m">start()</script>"#));
+    /// assert!(result.contains(r#"<link rel="preload" as="script" nonce="r4nd0m">"#));
+    /// ```
+    pub fn nonce(mut self, nonce: impl Into<String>) -> Self {
+        self.nonce = Some(nonce.into());
+        self
+    }
+
+    /// Render HTML template with data
+    pub fn render(&self, html: String, data: impl Serialize) -> Result<String, anyhow::Error> {
+        let mut rendered = String::new();
+        self.render_to_fmt(html, data, &mut rendered)?;
+        Ok(rendered)
+    }
+
+    /// Render HTML template with data, along with the warnings found while rendering
+    ///
+    /// See [`render_with_warnings`](crate::render_with_warnings).
+    pub fn render_with_warnings(
+        &self,
+        html: String,
+        data: impl Serialize,
+    ) -> Result<(String, Vec<Warning>), anyhow::Error> {
+        let (dom, lines, mut warnings) = parse(&html)?;
+        for warning in &mut warnings {
+            warning.file = self.file.clone();
+        }
+        warnings.extend(render_document(&dom.document, lines, data, self)?);
+
+        let mut rendered = String::new();
+        let mut writer = FmtWriter::new(&mut rendered);
+        write_document(&dom.document, &mut writer, self)?;
+        writer.finish()?;
+        Ok((rendered, warnings))
+    }
+
+    /// Render a single-file component with data as its props
+    ///
+    /// See [`Component`].
+    pub fn render_component(
+        &self,
+        component: &Component,
+        data: impl Serialize,
+    ) -> Result<String, anyhow::Error> {
+        let options = Options {
+            script: component
+                .script()
+                .map(str::to_string)
+                .or_else(|| self.script.clone()),
+            scope_id: component.scope_id().map(str::to_string),
+            css: Some(component.css()),
+            ..self.clone()
+        };
+        let (dom, lines, _) = parse_from(component.template(), component.template_line())?;
+        render_document(&dom.document, lines, data, &options)?;
+
+        let mut rendered = String::new();
+        let mut writer = FmtWriter::new(&mut rendered);
+        write_document(&dom.document, &mut writer, &options)?;
+        writer.finish()?;
+        Ok(rendered)
+    }
+
+    /// Render the template the [`loader`](Self::loader) has under a name
+    ///
+    /// Errors in the template, and in the templates it includes, carry their name in
+    /// [`TemplateError::file`](crate::TemplateError::file).
+    pub fn render_template(
+        &self,
+        name: &str,
+        data: impl Serialize,
+    ) -> Result<String, anyhow::Error> {
+        let html = self.loader.load(name)?;
+        let options = Options {
+            file: Some(name.to_string()),
+            ..self.clone()
+        };
+        options.render(html, data)
+    }
+
+    /// Render HTML template with data into an [`io::Write`] sink
+    pub fn render_to_writer(
+        &self,
+        html: String,
+        data: impl Serialize,
+        writer: &mut impl io::Write,
+    ) -> Result<(), anyhow::Error> {
+        let (dom, lines, _) = parse(&html)?;
+        render_document(&dom.document, lines, data, self)?;
+        write_document(&dom.document, writer, self)
+    }
+
+    /// Render HTML template with data into a [`fmt::Write`] sink
+    pub fn render_to_fmt(
+        &self,
+        html: String,
+        data: impl Serialize,
+        writer: &mut impl fmt::Write,
+    ) -> Result<(), anyhow::Error> {
+        let mut writer = FmtWriter::new(writer);
+        self.render_to_writer(html, data, &mut writer)?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    /// Render HTML template with data as a stream of chunks
+    ///
+    /// See [`RenderStream`]. The styles of [`inject_styles`](Self::inject_styles) are only
+    /// known once the whole document has rendered, so the two can't be combined.
+    pub fn render_stream(
+        &self,
+        html: String,
+        data: impl Serialize,
+    ) -> Result<RenderStream, anyhow::Error> {
+        if self.inject_styles {
+            anyhow::bail!(
+                "`inject_styles` can't be used with `render_stream`, as `<head>` is sent before the components render"
+            );
+        }
+        let (dom, lines, _) = parse(&html)?;
+        let state = state_element(&data, self)?;
+        Ok(RenderStream::new(
+            Rc::clone(&dom.document),
+            Engine::new(data, lines, self)?,
+            self.clone(),
+            state,
+        ))
+    }
+}
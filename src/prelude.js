@@ -0,0 +1,147 @@
+// Formatting helpers installed by `Options::prelude`, available as plain functions in bindings.
+// Evaluates to an object of the helpers; number formatting follows the default locale.
+(() => {
+    const MONTHS = [
+        "January", "February", "March", "April", "May", "June",
+        "July", "August", "September", "October", "November", "December",
+    ];
+    const DAYS = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+    const BYTES = ["B", "KB", "MB", "GB", "TB", "PB"];
+    const CURRENCIES = {
+        USD: ["$", 2],
+        EUR: ["€", 2],
+        GBP: ["£", 2],
+        JPY: ["¥", 0],
+        CNY: ["CN¥", 2],
+        INR: ["₹", 2],
+    };
+    const UNITS = [
+        ["year", 365 * 24 * 3600],
+        ["month", 30 * 24 * 3600],
+        ["week", 7 * 24 * 3600],
+        ["day", 24 * 3600],
+        ["hour", 3600],
+        ["minute", 60],
+        ["second", 1],
+    ];
+
+    const toDate = (value) => (value instanceof Date ? value : new Date(value));
+    const pad = (value, length = 2) => String(value).padStart(length, "0");
+
+    // Format a date with strftime directives, or as ISO 8601 with "iso".
+    // Uses local time unless `utc` is set.
+    const formatDate = (value, format = "%Y-%m-%d", { utc = false } = {}) => {
+        const date = toDate(value);
+        if (Number.isNaN(date.getTime())) {
+            return "";
+        }
+        if (format === "iso") {
+            return date.toISOString();
+        }
+
+        const get = (name) => date[utc ? `getUTC${name}` : `get${name}`]();
+        const [year, month, day, hours] = ["FullYear", "Month", "Date", "Hours"].map(get);
+        const directives = {
+            Y: () => year,
+            y: () => pad(year % 100),
+            m: () => pad(month + 1),
+            d: () => pad(day),
+            e: () => day,
+            H: () => pad(hours),
+            I: () => pad(hours % 12 || 12),
+            M: () => pad(get("Minutes")),
+            S: () => pad(get("Seconds")),
+            L: () => pad(get("Milliseconds"), 3),
+            p: () => (hours < 12 ? "AM" : "PM"),
+            B: () => MONTHS[month],
+            b: () => MONTHS[month].slice(0, 3),
+            A: () => DAYS[get("Day")],
+            a: () => DAYS[get("Day")].slice(0, 3),
+            "%": () => "%",
+        };
+        return format.replace(/%([a-zA-Z%])/g, (match, directive) =>
+            directive in directives ? String(directives[directive]()) : match,
+        );
+    };
+
+    // Number with locale grouping, and exactly `digits` fraction digits when given
+    const formatNumber = (value, digits) => {
+        const options = digits === undefined
+            ? {}
+            : { minimumFractionDigits: digits, maximumFractionDigits: digits };
+        return new Intl.NumberFormat(undefined, options).format(value);
+    };
+
+    // Locale currency format; engines without currency formatting get the symbol, or the code,
+    // before the number with the fraction digits of the currency
+    const formatCurrency = (value, currency = "USD") => {
+        try {
+            return new Intl.NumberFormat(undefined, { style: "currency", currency }).format(value);
+        } catch {
+            const [symbol, digits] = CURRENCIES[currency] ?? [`${currency} `, 2];
+            const sign = value < 0 ? "-" : "";
+            return `${sign}${symbol}${formatNumber(Math.abs(value), digits)}`;
+        }
+    };
+
+    // Size in bytes with binary multiples, like "1.5 MB"
+    const formatBytes = (bytes, digits = 1) => {
+        let size = Number(bytes);
+        let unit = 0;
+        while (Math.abs(size) >= 1024 && unit < BYTES.length - 1) {
+            size /= 1024;
+            unit += 1;
+        }
+        const text = unit === 0 ? String(size) : size.toFixed(digits).replace(/\.0+$/, "");
+        return `${text} ${BYTES[unit]}`;
+    };
+
+    // Distance from `now` in the largest whole unit, like "3 days ago" or "in 2 hours"
+    const timeAgo = (value, now = Date.now()) => {
+        const seconds = Math.round((toDate(value).getTime() - toDate(now).getTime()) / 1000);
+        if (Number.isNaN(seconds)) {
+            return "";
+        }
+        if (Math.abs(seconds) < 10) {
+            return "just now";
+        }
+        const [unit, size] = UNITS.find(([, size]) => Math.abs(seconds) >= size);
+        const count = Math.floor(Math.abs(seconds) / size);
+        const text = `${count} ${pluralize(count, unit)}`;
+        return seconds < 0 ? `${text} ago` : `in ${text}`;
+    };
+
+    // Shorten text to at most `length` characters, ending with `suffix` when cut
+    const truncate = (text, length = 100, suffix = "…") => {
+        const chars = Array.from(String(text ?? ""));
+        if (chars.length <= length) {
+            return chars.join("");
+        }
+        const kept = Math.max(length - Array.from(suffix).length, 0);
+        return chars.slice(0, kept).join("").trimEnd() + suffix;
+    };
+
+    // Lowercase ASCII words joined by dashes, with accents removed
+    const slugify = (text) =>
+        String(text ?? "")
+            .normalize("NFKD")
+            .replace(/[\u0300-\u036f]/g, "")
+            .toLowerCase()
+            .replace(/[^a-z0-9]+/g, "-")
+            .replace(/^-+|-+$/g, "");
+
+    // Singular or plural form of a word for `count`
+    const pluralize = (count, singular, plural = `${singular}s`) =>
+        Math.abs(count) === 1 ? singular : plural;
+
+    return {
+        formatDate,
+        formatNumber,
+        formatCurrency,
+        formatBytes,
+        timeAgo,
+        truncate,
+        slugify,
+        pluralize,
+    };
+})()
@@ -0,0 +1,288 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::{Arc, Mutex, mpsc};
+use std::thread;
+
+use markup5ever_rcdom::Handle;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::sink::Lines;
+use crate::{Options, clone_node, parse, render_document, write_document};
+
+// Parsed templates kept per worker; the least recently used one is evicted past this
+const TEMPLATE_CACHE_SIZE: usize = 64;
+
+type Reply = Box<dyn FnOnce(Result<String, anyhow::Error>) + Send>;
+
+struct Job {
+    html: String,
+    data: JsonValue,
+    reply: Reply,
+}
+
+/// Thread-safe rendering service backed by a pool of worker threads
+///
+/// Each worker owns its own JavaScript engine and a cache of parsed templates,
+/// so `Renderer` itself is `Send + Sync` and cheap to clone into request handlers.
+/// Workers shut down once every clone has been dropped.
+///
+/// # Examples
+///
+/// ```
+/// use prevue::Renderer;
+/// use serde_json::json;
+///
+/// let renderer = Renderer::new(2);
+/// let html = r#"<p>{{ message }}</p>"#;
+/// let result = renderer.render(html, json!({ "message": "Hello" })).unwrap();
+/// assert!(result.contains("<p>Hello</p>"));
+/// ```
+#[derive(Clone)]
+pub struct Renderer {
+    sender: mpsc::Sender<Job>,
+}
+
+impl Renderer {
+    /// Create a renderer with the given number of worker threads (at least one)
+    pub fn new(threads: usize) -> Self {
+        Self::with_options(threads, Options::default())
+    }
+
+    /// Create a renderer whose workers render with the given options
+    pub fn with_options(threads: usize, options: Options) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for i in 0..threads.max(1) {
+            let receiver = Arc::clone(&receiver);
+            let options = options.clone();
+            thread::Builder::new()
+                .name(format!("prevue-worker-{i}"))
+                .spawn(move || worker(receiver, options))
+                .expect("failed to spawn render worker");
+        }
+
+        Self { sender }
+    }
+
+    /// Render HTML template with data on a worker thread, blocking until it is done
+    pub fn render(
+        &self,
+        html: impl Into<String>,
+        data: impl Serialize,
+    ) -> Result<String, anyhow::Error> {
+        let (tx, rx) = mpsc::channel();
+        self.dispatch(
+            html.into(),
+            data,
+            Box::new(move |result| {
+                let _ = tx.send(result);
+            }),
+        )?;
+        rx.recv()
+            .map_err(|_| anyhow::anyhow!("render worker terminated"))?
+    }
+
+    /// Render HTML template with data on a worker thread without blocking the caller
+    #[cfg(feature = "async")]
+    pub async fn render_async(
+        &self,
+        html: impl Into<String>,
+        data: impl Serialize,
+    ) -> Result<String, anyhow::Error> {
+        let (completer, pending) = oneshot::channel();
+        self.dispatch(
+            html.into(),
+            data,
+            Box::new(move |result| completer.complete(result)),
+        )?;
+        pending.await
+    }
+
+    fn dispatch(
+        &self,
+        html: String,
+        data: impl Serialize,
+        reply: Reply,
+    ) -> Result<(), anyhow::Error> {
+        let data = serde_json::to_value(data)?;
+        self.sender
+            .send(Job { html, data, reply })
+            .map_err(|_| anyhow::anyhow!("render workers are not running"))
+    }
+}
+
+impl Default for Renderer {
+    /// Create a renderer with one worker per available CPU
+    fn default() -> Self {
+        Self::new(thread::available_parallelism().map_or(1, |n| n.get()))
+    }
+}
+
+// Parsed templates keyed by a hash of their source
+#[derive(Default)]
+struct TemplateCache {
+    entries: HashMap<u64, CachedTemplate>,
+    clock: u64,
+}
+
+struct CachedTemplate {
+    // Kept to tell hash collisions apart
+    source: String,
+    document: Handle,
+    lines: Lines,
+    used: u64,
+}
+
+impl TemplateCache {
+    fn get_or_parse(&mut self, source: &str) -> Result<&CachedTemplate, anyhow::Error> {
+        let mut hasher = DefaultHasher::new();
+        source.hash(&mut hasher);
+        let key = hasher.finish();
+        self.clock += 1;
+
+        if self
+            .entries
+            .get(&key)
+            .is_none_or(|cached| cached.source != source)
+        {
+            let (dom, lines, _) = parse(source)?;
+            if !self.entries.contains_key(&key) && self.entries.len() >= TEMPLATE_CACHE_SIZE {
+                self.evict();
+            }
+            self.entries.insert(
+                key,
+                CachedTemplate {
+                    source: source.to_string(),
+                    document: dom.document,
+                    lines,
+                    used: 0,
+                },
+            );
+        }
+
+        let cached = self
+            .entries
+            .get_mut(&key)
+            .expect("template was just cached");
+        cached.used = self.clock;
+        Ok(cached)
+    }
+
+    // Drop the least recently used template
+    fn evict(&mut self) {
+        if let Some(key) = self
+            .entries
+            .iter()
+            .min_by_key(|(_, cached)| cached.used)
+            .map(|(key, _)| *key)
+        {
+            self.entries.remove(&key);
+        }
+    }
+}
+
+fn worker(receiver: Arc<Mutex<mpsc::Receiver<Job>>>, options: Options) {
+    let mut templates = TemplateCache::default();
+
+    loop {
+        let job = {
+            let Ok(receiver) = receiver.lock() else {
+                return;
+            };
+            match receiver.recv() {
+                Ok(job) => job,
+                Err(_) => return,
+            }
+        };
+
+        let result = catch_unwind(AssertUnwindSafe(|| -> Result<String, anyhow::Error> {
+            let template = templates.get_or_parse(&job.html)?;
+            let document = clone_node(&template.document);
+            let mut lines = Lines::default();
+            lines.copy_from(&template.lines, &template.document, &document);
+            render_document(&document, lines, &job.data, &options)?;
+
+            let mut buffer = Vec::new();
+            write_document(&document, &mut buffer, &options)?;
+            Ok(String::from_utf8(buffer)?)
+        }))
+        .unwrap_or_else(|_| Err(anyhow::anyhow!("render worker panicked")));
+
+        (job.reply)(result);
+    }
+}
+
+#[cfg(feature = "async")]
+mod oneshot {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll, Waker};
+
+    type RenderResult = Result<String, anyhow::Error>;
+
+    #[derive(Default)]
+    struct Slot {
+        result: Option<RenderResult>,
+        waker: Option<Waker>,
+        closed: bool,
+    }
+
+    pub(super) fn channel() -> (Completer, Pending) {
+        let slot = Arc::new(Mutex::new(Slot::default()));
+        (Completer(Some(Arc::clone(&slot))), Pending(slot))
+    }
+
+    pub(super) struct Completer(Option<Arc<Mutex<Slot>>>);
+
+    impl Completer {
+        pub(super) fn complete(mut self, result: RenderResult) {
+            if let Some(slot) = self.0.take() {
+                fill(&slot, Some(result));
+            }
+        }
+    }
+
+    impl Drop for Completer {
+        // Wake the pending side if the job was dropped without a result
+        fn drop(&mut self) {
+            if let Some(slot) = self.0.take() {
+                fill(&slot, None);
+            }
+        }
+    }
+
+    fn fill(slot: &Mutex<Slot>, result: Option<RenderResult>) {
+        let waker = {
+            let mut slot = slot.lock().unwrap_or_else(|e| e.into_inner());
+            slot.result = result;
+            slot.closed = true;
+            slot.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    pub(super) struct Pending(Arc<Mutex<Slot>>);
+
+    impl Future for Pending {
+        type Output = RenderResult;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<RenderResult> {
+            let mut slot = self.0.lock().unwrap_or_else(|e| e.into_inner());
+            if !slot.closed {
+                slot.waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+            Poll::Ready(
+                slot.result
+                    .take()
+                    .unwrap_or_else(|| Err(anyhow::anyhow!("render worker terminated"))),
+            )
+        }
+    }
+}
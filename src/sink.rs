@@ -0,0 +1,303 @@
+use html5ever::tendril::StrTendril;
+use html5ever::tree_builder::{ElementFlags, NodeOrText, QuirksMode, TreeSink};
+use html5ever::{Attribute, QualName};
+use markup5ever_rcdom::{Handle, Node, NodeData, RcDom};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::{Rc, Weak};
+
+use crate::warning::{Warning, WarningKind};
+
+// Source line of parsed nodes, keyed by node address.
+// Holding a weak reference keeps the address from being reused while the entry exists.
+#[derive(Default)]
+pub(crate) struct Lines(HashMap<usize, (Weak<Node>, usize)>);
+
+impl Lines {
+    pub fn get(&self, node: &Handle) -> Option<usize> {
+        self.0.get(&key(node)).map(|(_, line)| *line)
+    }
+
+    pub fn set(&mut self, node: &Handle, line: usize) {
+        self.0.insert(key(node), (Rc::downgrade(node), line));
+    }
+
+    // Copy lines of a subtree onto its clone
+    pub fn copy(&mut self, from: &Handle, to: &Handle) {
+        for (from, to) in zip_tree(from, to) {
+            if let Some(line) = self.get(&from) {
+                self.set(&to, line);
+            }
+        }
+    }
+
+    // Copy lines of a subtree of another template onto its clone
+    pub fn copy_from(&mut self, lines: &Lines, from: &Handle, to: &Handle) {
+        for (from, to) in zip_tree(from, to) {
+            if let Some(line) = lines.get(&from) {
+                self.set(&to, line);
+            }
+        }
+    }
+}
+
+// Pair up the nodes of a subtree with those of its clone, including template contents
+fn zip_tree(from: &Handle, to: &Handle) -> Vec<(Handle, Handle)> {
+    let mut pairs = vec![(Rc::clone(from), Rc::clone(to))];
+    for (from, to) in from
+        .children
+        .borrow()
+        .iter()
+        .zip(to.children.borrow().iter())
+    {
+        pairs.extend(zip_tree(from, to));
+    }
+    if let (
+        NodeData::Element {
+            template_contents: from,
+            ..
+        },
+        NodeData::Element {
+            template_contents: to,
+            ..
+        },
+    ) = (&from.data, &to.data)
+        && let (Some(from), Some(to)) = (from.borrow().as_ref(), to.borrow().as_ref())
+    {
+        pairs.extend(zip_tree(from, to));
+    }
+    pairs
+}
+
+fn key(node: &Handle) -> usize {
+    Rc::as_ptr(node) as usize
+}
+
+// Tree sink building an `RcDom` while recording the source line of each node, and warnings
+// for parse errors that change the template
+#[derive(Default)]
+pub(crate) struct LineSink {
+    dom: RcDom,
+    line: Cell<u64>,
+    // Lines of the source file before the parsed HTML
+    offset: u64,
+    lines: RefCell<Lines>,
+    warnings: RefCell<Vec<Warning>>,
+}
+
+impl LineSink {
+    pub fn starting_at(first_line: usize) -> Self {
+        Self {
+            offset: first_line.saturating_sub(1) as u64,
+            ..Self::default()
+        }
+    }
+
+    fn current_line(&self) -> usize {
+        self.line.get().max(self.offset + 1) as usize
+    }
+
+    fn record(&self, node: &Handle) {
+        let line = self.current_line();
+        match &node.data {
+            // Text is appended in chunks as it is tokenized, after the newlines of
+            // each chunk have been counted
+            NodeData::Text { contents } => {
+                let newlines = contents.borrow().matches('\n').count();
+                self.lines
+                    .borrow_mut()
+                    .set(node, line.saturating_sub(newlines).max(1));
+            }
+            _ => self.lines.borrow_mut().set(node, line),
+        }
+    }
+
+    // Record the text node that received appended text
+    fn record_text(&self, node: Option<Handle>) {
+        if let Some(node) = node
+            && matches!(node.data, NodeData::Text { .. })
+        {
+            self.record(&node);
+        }
+    }
+}
+
+fn last_child(parent: &Handle) -> Option<Handle> {
+    parent.children.borrow().last().cloned()
+}
+
+fn previous_sibling(node: &Handle) -> Option<Handle> {
+    let parent = node.parent.take()?;
+    node.parent.set(Some(Weak::clone(&parent)));
+    let parent = parent.upgrade()?;
+    let children = parent.children.borrow();
+    let pos = children.iter().position(|c| Rc::ptr_eq(c, node))?;
+    pos.checked_sub(1).map(|prev| Rc::clone(&children[prev]))
+}
+
+fn has_parent(node: &Handle) -> bool {
+    let parent = node.parent.take();
+    let exists = parent.as_ref().is_some_and(|p| p.upgrade().is_some());
+    node.parent.set(parent);
+    exists
+}
+
+impl TreeSink for LineSink {
+    type Handle = Handle;
+    type Output = (RcDom, Lines, Vec<Warning>);
+    type ElemName<'a>
+        = <RcDom as TreeSink>::ElemName<'a>
+    where
+        Self: 'a;
+
+    fn finish(self) -> Self::Output {
+        (
+            self.dom,
+            self.lines.into_inner(),
+            self.warnings.into_inner(),
+        )
+    }
+
+    fn parse_error(&self, msg: Cow<'static, str>) {
+        if msg.starts_with("Duplicate attribute") {
+            self.warnings.borrow_mut().push(Warning::new(
+                WarningKind::DuplicateAttribute,
+                Some(self.current_line()),
+                "duplicate attribute; the first one is kept",
+            ));
+        }
+        self.dom.parse_error(msg);
+    }
+
+    fn get_document(&self) -> Handle {
+        self.dom.get_document()
+    }
+
+    fn elem_name<'a>(&'a self, target: &'a Handle) -> Self::ElemName<'a> {
+        self.dom.elem_name(target)
+    }
+
+    fn create_element(&self, name: QualName, attrs: Vec<Attribute>, flags: ElementFlags) -> Handle {
+        let node = self.dom.create_element(name, attrs, flags);
+        self.record(&node);
+        node
+    }
+
+    fn create_comment(&self, text: StrTendril) -> Handle {
+        let node = self.dom.create_comment(text);
+        self.record(&node);
+        node
+    }
+
+    fn create_pi(&self, target: StrTendril, data: StrTendril) -> Handle {
+        self.dom.create_pi(target, data)
+    }
+
+    fn append(&self, parent: &Handle, child: NodeOrText<Handle>) {
+        let is_text = matches!(child, NodeOrText::AppendText(_));
+        self.dom.append(parent, child);
+        if is_text {
+            self.record_text(last_child(parent));
+        }
+    }
+
+    fn append_based_on_parent_node(
+        &self,
+        element: &Handle,
+        prev_element: &Handle,
+        child: NodeOrText<Handle>,
+    ) {
+        if has_parent(element) {
+            self.append_before_sibling(element, child);
+        } else {
+            self.append(prev_element, child);
+        }
+    }
+
+    fn append_doctype_to_document(
+        &self,
+        name: StrTendril,
+        public_id: StrTendril,
+        system_id: StrTendril,
+    ) {
+        self.dom
+            .append_doctype_to_document(name, public_id, system_id);
+    }
+
+    fn mark_script_already_started(&self, node: &Handle) {
+        self.dom.mark_script_already_started(node);
+    }
+
+    fn pop(&self, node: &Handle) {
+        self.dom.pop(node);
+    }
+
+    fn get_template_contents(&self, target: &Handle) -> Handle {
+        self.dom.get_template_contents(target)
+    }
+
+    fn same_node(&self, x: &Handle, y: &Handle) -> bool {
+        self.dom.same_node(x, y)
+    }
+
+    fn set_quirks_mode(&self, mode: QuirksMode) {
+        self.dom.set_quirks_mode(mode);
+    }
+
+    fn append_before_sibling(&self, sibling: &Handle, new_node: NodeOrText<Handle>) {
+        let is_text = matches!(new_node, NodeOrText::AppendText(_));
+        self.dom.append_before_sibling(sibling, new_node);
+        if is_text {
+            self.record_text(previous_sibling(sibling));
+        }
+    }
+
+    fn add_attrs_if_missing(&self, target: &Handle, attrs: Vec<Attribute>) {
+        self.dom.add_attrs_if_missing(target, attrs);
+    }
+
+    fn associate_with_form(
+        &self,
+        target: &Handle,
+        form: &Handle,
+        nodes: (&Handle, Option<&Handle>),
+    ) {
+        self.dom.associate_with_form(target, form, nodes);
+    }
+
+    fn remove_from_parent(&self, target: &Handle) {
+        self.dom.remove_from_parent(target);
+    }
+
+    fn reparent_children(&self, node: &Handle, new_parent: &Handle) {
+        self.dom.reparent_children(node, new_parent);
+    }
+
+    fn is_mathml_annotation_xml_integration_point(&self, handle: &Handle) -> bool {
+        self.dom.is_mathml_annotation_xml_integration_point(handle)
+    }
+
+    fn set_current_line(&self, line_number: u64) {
+        self.line.set(line_number + self.offset);
+        self.dom.set_current_line(line_number);
+    }
+
+    fn allow_declarative_shadow_roots(&self, intended_parent: &Handle) -> bool {
+        self.dom.allow_declarative_shadow_roots(intended_parent)
+    }
+
+    fn attach_declarative_shadow(
+        &self,
+        location: &Handle,
+        template: &Handle,
+        attrs: &[Attribute],
+    ) -> bool {
+        self.dom
+            .attach_declarative_shadow(location, template, attrs)
+    }
+
+    fn maybe_clone_an_option_into_selectedcontent(&self, option: &Handle) {
+        self.dom.maybe_clone_an_option_into_selectedcontent(option);
+    }
+}
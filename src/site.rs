@@ -0,0 +1,471 @@
+use anyhow::Context;
+use regex::Regex;
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use crate::loader::is_relative_inside;
+use crate::{Component, DataFormat, Loader, Options};
+
+// `alias in key.path` of a page generator
+static SYNTAX_GENERATOR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\s*(?<alias>[A-Za-z_$][\w$]*)\s+(?:in|of)\s+(?<source>[A-Za-z_$][\w$]*(?:\.[A-Za-z_$][\w$]*)*)\s*$",
+    )
+    .unwrap()
+});
+
+// `[param]` segment in the file name of a generated page
+static SYNTAX_PARAM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[(?<param>[^\[\]/]+)\]").unwrap());
+
+// Directories of the templates, components and data every page may use
+const SHARED_DIRS: [&str; 4] = ["layouts", "partials", "components", "data"];
+
+// Extensions of data files, in the order they are looked up
+const DATA_EXTENSIONS: [&str; 4] = ["json", "yaml", "yml", "toml"];
+
+// Fingerprints of the outputs of the last build, in the output directory
+const MANIFEST: &str = ".prevue-manifest.json";
+
+/// Static site built from a directory of templates
+///
+/// The site directory holds:
+///
+/// - `pages/`: page templates, each written to the same path in the output directory. A data
+///   file next to a page, like `about.json` for `about.html`, adds to its data.
+/// - `data/`: data every page sees, under the name of each file, like `posts` for `posts.yaml`
+/// - `layouts/`: templates wrapping pages, which include the page with
+///   `<include :src="$page"></include>`. A page uses the [`layout`](Self::layout) of the site
+///   unless its data sets `layout` to another file of `layouts/`, or to `false`.
+/// - `partials/` and `components/`: templates pages include by path, like
+///   `partials/nav.html`, and `.vue` components they render with `<component is="Name">`
+/// - `public/`: files copied as they are, like images and style sheets
+///
+/// A page whose file name has a `[param]`, like `posts/[slug].html`, is generated once for
+/// each item of a collection. Its data sets `$for` to `"post in posts"`, `posts` being an
+/// array or object of the data, and each page sees its item as `post` and is named after
+/// `post.slug`, or after the item itself when it isn't an object. Pages also see their output
+/// path as `$path`.
+///
+/// Builds are incremental: a page is rendered again when its template, its data or a file of
+/// `layouts/`, `partials/`, `components/` or `data/` changed, and outputs no page produces
+/// anymore are removed. Changing the options calls for a clean output directory.
+///
+/// # Examples
+///
+/// ```no_run
+/// use prevue::{Options, Site, Whitespace};
+///
+/// let build = Site::new("site", "site/_build")
+///     .layout("base.html")
+///     .options(Options::new().whitespace(Whitespace::Minify))
+///     .build()
+///     .unwrap();
+/// println!("{} pages rendered", build.rendered.len());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Site {
+    root: PathBuf,
+    out: PathBuf,
+    options: Options,
+    layout: Option<String>,
+}
+
+/// Outputs of a [`Site::build`], relative to the output directory
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Build {
+    /// Pages rendered because they are new or their inputs changed
+    pub rendered: Vec<PathBuf>,
+    /// Files of `public/` copied because they are new or changed
+    pub copied: Vec<PathBuf>,
+    /// Pages and files left as the last build wrote them
+    pub unchanged: Vec<PathBuf>,
+    /// Outputs of the last build that nothing produces anymore
+    pub removed: Vec<PathBuf>,
+}
+
+// Page to render, before its fingerprint is checked
+struct Page {
+    // Loader name of the template, like `pages/about.html`
+    template: String,
+    source: String,
+    layout: Option<String>,
+    data: Map<String, Value>,
+    path: String,
+}
+
+impl Site {
+    /// Create a site from the directory `root`, written to the directory `out`
+    pub fn new(root: impl Into<PathBuf>, out: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            out: out.into(),
+            options: Options::new(),
+            layout: None,
+        }
+    }
+
+    /// Set the options pages are rendered with
+    ///
+    /// The loader is replaced by one for the site directory, and the components of
+    /// `components/` are added to those of the options.
+    pub fn options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set the file of `layouts/` that wraps pages without a `layout` of their own
+    pub fn layout(mut self, name: impl Into<String>) -> Self {
+        self.layout = Some(name.into());
+        self
+    }
+
+    /// Render the pages that changed since the last build and copy the public files
+    pub fn build(&self) -> Result<Build, anyhow::Error> {
+        let options = self.page_options()?;
+        let shared = self.shared_fingerprint()?;
+        let previous = self.read_manifest();
+        let mut manifest = BTreeMap::new();
+        let mut build = Build::default();
+
+        for page in self.pages()? {
+            let target = self.out.join(&page.path);
+            let data = Value::Object(page.data);
+            let fingerprint = fingerprint(format!(
+                "{shared}\0{}\0{:?}\0{}\0{data}",
+                page.template, page.layout, page.source
+            ));
+            if manifest.contains_key(&page.path) {
+                anyhow::bail!("`{}` is written by more than one page", page.path);
+            }
+
+            if previous.get(&page.path) == Some(&fingerprint) && target.is_file() {
+                build.unchanged.push(PathBuf::from(&page.path));
+            } else {
+                let html = match &page.layout {
+                    Some(layout) => {
+                        let mut data = data;
+                        data["$page"] = Value::String(page.template.clone());
+                        options.render_template(&format!("layouts/{layout}"), data)?
+                    }
+                    None => options.render_template(&page.template, data)?,
+                };
+                write(&target, html.as_bytes())?;
+                build.rendered.push(PathBuf::from(&page.path));
+            }
+            manifest.insert(page.path, fingerprint);
+        }
+
+        let public = self.root.join("public");
+        for file in files(&public)? {
+            let path = path_key(&file);
+            let contents = fs::read(public.join(&file))?;
+            let fingerprint = fingerprint(&contents);
+            if manifest.contains_key(&path) {
+                anyhow::bail!("`public/{path}` has the path of a page");
+            }
+
+            let target = self.out.join(&file);
+            if previous.get(&path) == Some(&fingerprint) && target.is_file() {
+                build.unchanged.push(file);
+            } else {
+                write(&target, &contents)?;
+                build.copied.push(file);
+            }
+            manifest.insert(path, fingerprint);
+        }
+
+        for path in previous.keys().filter(|path| !manifest.contains_key(*path)) {
+            match fs::remove_file(self.out.join(path)) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err.into()),
+                _ => build.removed.push(PathBuf::from(path)),
+            }
+        }
+
+        write(
+            &self.out.join(MANIFEST),
+            serde_json::to_string_pretty(&json!({ "files": manifest }))?.as_bytes(),
+        )?;
+        Ok(build)
+    }
+
+    // Options with the site's loader and components
+    fn page_options(&self) -> Result<Options, anyhow::Error> {
+        let mut options = self
+            .options
+            .clone()
+            .loader(Loader::new().dir(self.root.clone()));
+        let dir = self.root.join("components");
+        for file in files(&dir)? {
+            if file.extension().is_none_or(|extension| extension != "vue") {
+                continue;
+            }
+            let Some(name) = file.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let source = fs::read_to_string(dir.join(&file))?;
+            let component = Component::parse(&source)
+                .with_context(|| format!("invalid component `components/{}`", path_key(&file)))?;
+            options = options.component(name, component);
+        }
+        Ok(options)
+    }
+
+    // Hash of the files every page may use
+    fn shared_fingerprint(&self) -> Result<String, anyhow::Error> {
+        let mut shared = Vec::new();
+        for dir in SHARED_DIRS {
+            let dir = self.root.join(dir);
+            for file in files(&dir)? {
+                shared.extend_from_slice(path_key(&file).as_bytes());
+                shared.push(0);
+                shared.extend(fs::read(dir.join(&file))?);
+                shared.push(0);
+            }
+        }
+        Ok(fingerprint(&shared))
+    }
+
+    // Data of `data/`, by file name
+    fn site_data(&self) -> Result<Map<String, Value>, anyhow::Error> {
+        let dir = self.root.join("data");
+        let mut data = Map::new();
+        for file in files(&dir)? {
+            let (Some(format), Some(name)) = (
+                DataFormat::from_path(&file),
+                file.file_stem().and_then(|stem| stem.to_str()),
+            ) else {
+                continue;
+            };
+            if file.parent().is_some_and(|parent| parent != Path::new("")) {
+                continue;
+            }
+            data.insert(name.to_string(), read_data(&dir.join(&file), format)?);
+        }
+        Ok(data)
+    }
+
+    // Pages of `pages/`, with generated pages expanded
+    fn pages(&self) -> Result<Vec<Page>, anyhow::Error> {
+        let site_data = self.site_data()?;
+        let dir = self.root.join("pages");
+        let mut pages = Vec::new();
+
+        for file in files(&dir)? {
+            if file.extension().is_none_or(|extension| extension != "html") {
+                continue;
+            }
+            let path = path_key(&file);
+            let template = format!("pages/{path}");
+            let source = fs::read_to_string(dir.join(&file))?;
+
+            let mut data = site_data.clone();
+            for extension in DATA_EXTENSIONS {
+                let data_file = dir.join(&file).with_extension(extension);
+                let Some(format) = DataFormat::from_path(&data_file) else {
+                    continue;
+                };
+                if !data_file.is_file() {
+                    continue;
+                }
+                match read_data(&data_file, format)? {
+                    Value::Object(page_data) => data.extend(page_data),
+                    _ => anyhow::bail!("data of `{template}` must be an object"),
+                }
+                break;
+            }
+            let layout = match data.get("layout") {
+                Some(Value::String(layout)) => Some(layout.clone()),
+                Some(Value::Bool(false) | Value::Null) => None,
+                _ => self.layout.clone(),
+            };
+
+            let page = Page {
+                template,
+                source,
+                layout,
+                data,
+                path,
+            };
+            match page.data.get("$for").cloned() {
+                None if SYNTAX_PARAM.is_match(&page.path) => anyhow::bail!(
+                    "`{}` has a `[param]` in its name but no `$for` in its data",
+                    page.template
+                ),
+                None => pages.push(with_path(page)),
+                Some(Value::String(generator)) => generate(page, &generator, &mut pages)?,
+                Some(_) => anyhow::bail!(
+                    "`$for` of `{}` must be a string like `post in posts`",
+                    page.template
+                ),
+            }
+        }
+        Ok(pages)
+    }
+
+    // Fingerprints of the outputs of the last build, or none when there is no manifest
+    fn read_manifest(&self) -> BTreeMap<String, String> {
+        fs::read_to_string(self.out.join(MANIFEST))
+            .ok()
+            .and_then(|manifest| serde_json::from_str::<Value>(&manifest).ok())
+            .and_then(
+                |mut manifest| match manifest.get_mut("files").map(Value::take) {
+                    Some(Value::Object(files)) => Some(files),
+                    _ => None,
+                },
+            )
+            .map(|files| {
+                files
+                    .into_iter()
+                    // Outputs are removed by these paths, so only ones inside `out` are kept
+                    .filter(|(path, _)| {
+                        Path::new(path).file_name().is_some() && is_relative_inside(Path::new(path))
+                    })
+                    .filter_map(|(path, hash)| Some((path, hash.as_str()?.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+// Expand a page with a `$for` generator into a page for each item of its collection
+fn generate(page: Page, generator: &str, pages: &mut Vec<Page>) -> Result<(), anyhow::Error> {
+    let syntax = SYNTAX_GENERATOR.captures(generator).with_context(|| {
+        format!(
+            "`$for` of `{}` must be like `post in posts`, not `{generator}`",
+            page.template
+        )
+    })?;
+    let alias = &syntax["alias"];
+    let source = &syntax["source"];
+
+    let mut keys = source.split('.');
+    let first = keys.next().and_then(|key| page.data.get(key));
+    let items = match keys
+        .try_fold(first, |value, key| Some(value?.get(key)))
+        .flatten()
+    {
+        Some(Value::Array(items)) => items.clone(),
+        Some(Value::Object(items)) => items.values().cloned().collect(),
+        _ => anyhow::bail!(
+            "`$for` of `{}` reads `{source}`, which is not an array or object",
+            page.template
+        ),
+    };
+
+    for item in items {
+        let mut path = String::with_capacity(page.path.len());
+        let mut last = 0;
+        for param in SYNTAX_PARAM.captures_iter(&page.path) {
+            let range = param.get(0).unwrap().range();
+            let name = &param["param"];
+            let value = match &item {
+                Value::Object(fields) => fields.get(name),
+                item => Some(item),
+            };
+            let segment = match value {
+                Some(Value::String(segment)) => segment.clone(),
+                Some(Value::Number(segment)) => segment.to_string(),
+                _ => anyhow::bail!(
+                    "`[{name}]` of `{}` is not a string or number in {item}",
+                    page.template
+                ),
+            };
+            if segment.is_empty()
+                || segment.contains(['/', '\\'])
+                || segment == "."
+                || segment == ".."
+            {
+                anyhow::bail!(
+                    "`[{name}]` of `{}` is `{segment}`, which is not a file name",
+                    page.template
+                );
+            }
+            path.push_str(&page.path[last..range.start]);
+            path.push_str(&segment);
+            last = range.end;
+        }
+        path.push_str(&page.path[last..]);
+
+        let mut data = page.data.clone();
+        data.remove("$for");
+        data.insert(alias.to_string(), item);
+        pages.push(with_path(Page {
+            template: page.template.clone(),
+            source: page.source.clone(),
+            layout: page.layout.clone(),
+            data,
+            path,
+        }));
+    }
+    Ok(())
+}
+
+// Let the page see its output path as `$path`
+fn with_path(mut page: Page) -> Page {
+    page.data
+        .insert("$path".to_string(), Value::String(page.path.clone()));
+    page
+}
+
+fn read_data(path: &Path, format: DataFormat) -> Result<Value, anyhow::Error> {
+    let source = fs::read_to_string(path)?;
+    format
+        .parse(&source)
+        .with_context(|| format!("invalid data in `{}`", path.display()))
+}
+
+// Files under a directory, as sorted relative paths; none when it doesn't exist
+fn files(dir: &Path) -> Result<Vec<PathBuf>, anyhow::Error> {
+    fn collect(dir: &Path, relative: &Path, found: &mut Vec<PathBuf>) -> io::Result<()> {
+        for entry in fs::read_dir(dir.join(relative))? {
+            let entry = entry?;
+            let path = relative.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                collect(dir, &path, found)?;
+            } else {
+                found.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let mut found = Vec::new();
+    if dir.is_dir() {
+        collect(dir, Path::new(""), &mut found)?;
+    }
+    found.sort();
+    Ok(found)
+}
+
+// Relative path with `/` separators, as the loader and the manifest name files
+fn path_key(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn write(path: &Path, contents: &[u8]) -> Result<(), anyhow::Error> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, contents).with_context(|| format!("can't write `{}`", path.display()))
+}
+
+// Hash of the inputs of an output, stable across builds and wide enough that a changed input
+// doesn't pass for an unchanged one
+fn fingerprint(input: impl AsRef<[u8]>) -> String {
+    // 128-bit FNV-1a
+    let hash = input.as_ref().iter().fold(
+        0x6c62_272e_07bb_0142_62b8_2175_6295_c58d_u128,
+        |hash, byte| {
+            (hash ^ u128::from(*byte)).wrapping_mul(0x0000_0000_0100_0000_0000_0000_0000_013b)
+        },
+    );
+    format!("{hash:032x}")
+}
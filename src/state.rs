@@ -0,0 +1,97 @@
+use serde::Serialize;
+
+use crate::escape;
+
+/// Script that hands the render data to the client, as a `window` global
+///
+/// The data is embedded as JSON, escaped so it can't end the script or be read as HTML. The
+/// global is `window.__INITIAL_STATE__` unless [`name`](Self::name) says otherwise, and the
+/// script goes at the end of `<body>` unless [`location`](Self::location) says otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use prevue::{InitialState, Options, StateLocation};
+/// use serde_json::json;
+///
+/// let state = InitialState::new()
+///     .name("__DATA__")
+///     .location(StateLocation::Head)
+///     .nonce("r4nd0m");
+/// let html = r#"<p>{{ message }}</p>"#;
+/// let result = Options::new()
+///     .initial_state(state)
+///     .render(html.to_string(), json!({ "message": "</script>" }))
+///     .unwrap();
+/// assert_eq!(
+///     result,
+///     r#"<html><head><script nonce="r4nd0m">window.__DATA__={"message":"\u003c/script\u003e"};</script></head><body><p>&lt;/script&gt;</p></body></html>"#
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct InitialState {
+    pub(crate) name: String,
+    pub(crate) location: StateLocation,
+    pub(crate) nonce: Option<String>,
+}
+
+/// Where the initial state script goes in the rendered document
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StateLocation {
+    /// Last child of `<head>`
+    Head,
+    /// First child of `<body>`, before the content that reads it
+    BodyStart,
+    /// Last child of `<body>`
+    #[default]
+    BodyEnd,
+}
+
+impl Default for InitialState {
+    fn default() -> Self {
+        Self {
+            name: "__INITIAL_STATE__".to_string(),
+            location: StateLocation::default(),
+            nonce: None,
+        }
+    }
+}
+
+impl InitialState {
+    /// Create the script for `window.__INITIAL_STATE__` at the end of `<body>`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the name of the `window` property holding the data
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Set where the script goes
+    pub fn location(mut self, location: StateLocation) -> Self {
+        self.location = location;
+        self
+    }
+
+    /// Set the `nonce` attribute of the script, instead of the one of
+    /// [`Options::nonce`](crate::Options::nonce)
+    pub fn nonce(mut self, nonce: impl Into<String>) -> Self {
+        self.nonce = Some(nonce.into());
+        self
+    }
+
+    // Source of the script assigning the data, escaped so it can't end the element
+    pub(crate) fn script(&self, data: impl Serialize) -> Result<String, anyhow::Error> {
+        let json = escape::json(&serde_json::to_value(data)?);
+        Ok(if crate::is_identifier(&self.name) {
+            format!("window.{}={json};", self.name)
+        } else {
+            format!(
+                "window[{}]={json};",
+                escape::json(&self.name.as_str().into())
+            )
+        })
+    }
+}
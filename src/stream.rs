@@ -0,0 +1,371 @@
+use html5ever::serialize::{HtmlSerializer, SerializeOpts, Serializer, TraversalScope};
+use markup5ever_rcdom::{Handle, NodeData, SerializableHandle};
+use std::rc::Rc;
+
+use crate::engine::Engine;
+use crate::format::{self, format_siblings, newline};
+use crate::{
+    Options, StateLocation, Whitespace, hydrate_node, is_whitespace_text_node, traverse_child,
+};
+
+enum State {
+    Head,
+    Body {
+        children: Vec<Handle>,
+        next: usize,
+        emitted: usize,
+        in_if_chain: bool,
+        if_chain_hit: bool,
+    },
+    Tail,
+    Done,
+}
+
+/// Iterator over rendered chunks of a document
+///
+/// Created by [`render_stream`](crate::render_stream). The first chunk holds everything up to
+/// the opening `<body>` tag, followed by one chunk per completed top-level section of the body,
+/// and finally the closing tags. With [`Whitespace::Preserve`], the concatenated chunks equal
+/// the output of [`render`](crate::render); other modes format each section on its own.
+/// With [`Options::fragment`], the chunks before and after the body content are left out, except
+/// for leading elements like `<script>` that the parser moves to `<head>`.
+/// A template error ends the stream with that error.
+pub struct RenderStream {
+    document: Handle,
+    engine: Engine,
+    options: Options,
+    html: Option<Handle>,
+    body: Option<Handle>,
+    state: State,
+    // Initial state script, until it is inserted at its location
+    initial_state: Option<(StateLocation, Handle)>,
+}
+
+impl RenderStream {
+    pub(crate) fn new(
+        document: Handle,
+        engine: Engine,
+        options: Options,
+        initial_state: Option<(StateLocation, Handle)>,
+    ) -> Self {
+        let html = find_element(&document, "html");
+        let body = html.as_ref().and_then(|html| find_element(html, "body"));
+
+        Self {
+            document,
+            engine,
+            options,
+            html,
+            body,
+            state: State::Head,
+            initial_state,
+        }
+    }
+
+    // Render and serialize everything before the body content
+    fn head(&mut self) -> Result<String, anyhow::Error> {
+        let (Some(html), Some(body)) = (self.html.clone(), self.body.clone()) else {
+            // Not a regular document: render it as a single chunk
+            let mut in_if_chain = false;
+            let mut if_chain_hit = false;
+            for node in children_of(&self.document) {
+                traverse_child(&node, &mut self.engine, &mut in_if_chain, &mut if_chain_hit);
+            }
+            self.state = State::Done;
+            format::format(&self.document, self.options.whitespace);
+            return write_nodes(&children_of(&self.document), self.options.whitespace);
+        };
+
+        let whitespace = self.options.whitespace;
+        let before_html = children_before(&self.document, &html);
+        self.traverse_nodes(&before_html);
+        let before_body = children_before(&html, &body);
+        self.traverse_nodes(&before_body);
+        insert_state(&self.document, &mut self.initial_state, StateLocation::Head);
+
+        let buffer = if self.options.fragment {
+            // A fragment is the body content alone, after the leading elements the parser
+            // moved to the head
+            match find_element(&html, "head") {
+                Some(head) => write_siblings(&head, &children_of(&head), 0, whitespace)?,
+                None => String::new(),
+            }
+        } else {
+            let mut buffer = write_siblings(&self.document, &before_html, 0, whitespace)?;
+            hydrate_node(&html, &mut self.engine);
+            buffer.push_str(&write_start_tag(&html, whitespace)?);
+            buffer.push_str(&write_siblings(&html, &before_body, 0, whitespace)?);
+
+            hydrate_node(&body, &mut self.engine);
+            if whitespace == Whitespace::Pretty {
+                buffer.push_str(&newline(1));
+            }
+            buffer.push_str(&write_start_tag(&body, whitespace)?);
+            buffer
+        };
+
+        self.state = State::Body {
+            children: children_of(&body),
+            next: 0,
+            emitted: 0,
+            in_if_chain: false,
+            if_chain_hit: false,
+        };
+        insert_state(
+            &self.document,
+            &mut self.initial_state,
+            StateLocation::BodyStart,
+        );
+        Ok(buffer)
+    }
+
+    // Render the next top-level body section; returns None once the body is exhausted
+    fn body(&mut self) -> Option<Result<String, anyhow::Error>> {
+        let body = self.body.clone()?;
+        let whitespace = self.options.whitespace;
+        let depth = if self.options.fragment { 0 } else { 1 };
+        let State::Body {
+            children,
+            next,
+            emitted,
+            in_if_chain,
+            if_chain_hit,
+        } = &mut self.state
+        else {
+            return None;
+        };
+
+        while let Some(node) = children.get(*next).cloned() {
+            *next += 1;
+            traverse_child(&node, &mut self.engine, in_if_chain, if_chain_hit);
+
+            // Rendering the next node may still rewrite the text right before it,
+            // so only the nodes preceding that are final
+            let Some(following) = children.get(*next) else {
+                break;
+            };
+            let position = body
+                .children
+                .borrow()
+                .iter()
+                .position(|c| Rc::ptr_eq(c, following))
+                .unwrap_or(0);
+            let mut ready: Vec<Handle> = body
+                .children
+                .borrow()
+                .iter()
+                .take(position)
+                .skip(*emitted)
+                .cloned()
+                .collect();
+            if ready
+                .last()
+                .is_some_and(|last| matches!(last.data, NodeData::Text { .. }))
+            {
+                ready.pop();
+            }
+
+            // Trailing whitespace is formatted along with the section that follows it
+            while ready.last().is_some_and(is_whitespace_text_node) {
+                ready.pop();
+            }
+            if ready.is_empty() {
+                continue;
+            }
+            *emitted += ready.len();
+            return Some(write_siblings(&body, &ready, depth, whitespace));
+        }
+
+        insert_state(
+            &self.document,
+            &mut self.initial_state,
+            StateLocation::BodyEnd,
+        );
+        let rest: Vec<Handle> = body
+            .children
+            .borrow()
+            .iter()
+            .skip(*emitted)
+            .cloned()
+            .collect();
+        self.state = State::Tail;
+        if rest.is_empty() {
+            None
+        } else {
+            Some(write_siblings(&body, &rest, depth, whitespace))
+        }
+    }
+
+    // Render and serialize everything after the body content
+    fn tail(&mut self) -> Result<String, anyhow::Error> {
+        self.state = State::Done;
+        let (Some(html), Some(body)) = (self.html.clone(), self.body.clone()) else {
+            return Ok(String::new());
+        };
+
+        let whitespace = self.options.whitespace;
+        let pretty = whitespace == Whitespace::Pretty;
+        let mut buffer = String::new();
+        if pretty
+            && body
+                .children
+                .borrow()
+                .iter()
+                .any(|c| !is_whitespace_text_node(c))
+        {
+            buffer.push_str(&newline(1));
+        }
+        buffer.push_str(&write_end_tag(&body));
+
+        let after_body = children_after(&html, &body);
+        self.traverse_nodes(&after_body);
+        buffer.push_str(&write_siblings(&html, &after_body, 0, whitespace)?);
+
+        if pretty {
+            buffer.push_str(&newline(0));
+        }
+        buffer.push_str(&write_end_tag(&html));
+
+        let after_html = children_after(&self.document, &html);
+        self.traverse_nodes(&after_html);
+        buffer.push_str(&write_siblings(&self.document, &after_html, 0, whitespace)?);
+
+        if self.options.fragment {
+            buffer.clear();
+        }
+        Ok(buffer)
+    }
+
+    fn traverse_nodes(&mut self, nodes: &[Handle]) {
+        let mut in_if_chain = false;
+        let mut if_chain_hit = false;
+        for node in nodes {
+            traverse_child(node, &mut self.engine, &mut in_if_chain, &mut if_chain_hit);
+        }
+    }
+}
+
+impl Iterator for RenderStream {
+    type Item = Result<String, anyhow::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let chunk = match self.state {
+                State::Head => self.head(),
+                State::Body { .. } => match self.body() {
+                    Some(chunk) => chunk,
+                    None => continue,
+                },
+                State::Tail => self.tail(),
+                State::Done => return None,
+            };
+
+            if let Some(error) = self.engine.error.take() {
+                self.state = State::Done;
+                return Some(Err(error));
+            }
+            // Fragments have no chunks before and after the body content
+            if self.options.fragment && chunk.as_ref().is_ok_and(String::is_empty) {
+                continue;
+            }
+            return Some(chunk);
+        }
+    }
+}
+
+// Add the initial state script to the document when `location` is where it goes
+fn insert_state(
+    document: &Handle,
+    initial_state: &mut Option<(StateLocation, Handle)>,
+    location: StateLocation,
+) {
+    if let Some((_, element)) = initial_state.take_if(|(at, _)| *at == location) {
+        crate::insert_state(document, location, element);
+    }
+}
+
+fn include_node() -> SerializeOpts {
+    SerializeOpts {
+        traversal_scope: TraversalScope::IncludeNode,
+        ..Default::default()
+    }
+}
+
+// Format final children of `parent` and serialize them
+fn write_siblings(
+    parent: &Handle,
+    nodes: &[Handle],
+    depth: usize,
+    whitespace: Whitespace,
+) -> Result<String, anyhow::Error> {
+    let nodes = format_siblings(parent, nodes, depth, whitespace);
+    write_nodes(&nodes, whitespace)
+}
+
+fn write_nodes(nodes: &[Handle], whitespace: Whitespace) -> Result<String, anyhow::Error> {
+    let mut buffer = Vec::new();
+    for node in nodes {
+        format::serialize(
+            &mut buffer,
+            &SerializableHandle::from(Rc::clone(node)),
+            TraversalScope::IncludeNode,
+            whitespace,
+        )?;
+    }
+    Ok(String::from_utf8(buffer)?)
+}
+
+fn write_start_tag(element: &Handle, whitespace: Whitespace) -> Result<String, anyhow::Error> {
+    let mut buffer = Vec::new();
+    if let NodeData::Element { name, attrs, .. } = &element.data {
+        let attrs = attrs.borrow();
+        let attrs = attrs.iter().map(|attr| (&attr.name, &*attr.value));
+        if whitespace == Whitespace::Minify {
+            format::start_tag(&mut buffer, name.clone(), attrs)?;
+        } else {
+            HtmlSerializer::new(&mut buffer, include_node()).start_elem(name.clone(), attrs)?;
+        }
+    }
+    Ok(String::from_utf8(buffer)?)
+}
+
+fn write_end_tag(element: &Handle) -> String {
+    match &element.data {
+        NodeData::Element { name, .. } => format!("</{}>", name.local),
+        _ => String::new(),
+    }
+}
+
+pub(crate) fn find_element(parent: &Handle, local: &str) -> Option<Handle> {
+    parent
+        .children
+        .borrow()
+        .iter()
+        .find(|c| matches!(&c.data, NodeData::Element { name, .. } if name.local.as_ref() == local))
+        .cloned()
+}
+
+fn children_of(parent: &Handle) -> Vec<Handle> {
+    parent.children.borrow().iter().cloned().collect()
+}
+
+fn children_before(parent: &Handle, node: &Handle) -> Vec<Handle> {
+    parent
+        .children
+        .borrow()
+        .iter()
+        .take_while(|c| !Rc::ptr_eq(c, node))
+        .cloned()
+        .collect()
+}
+
+fn children_after(parent: &Handle, node: &Handle) -> Vec<Handle> {
+    parent
+        .children
+        .borrow()
+        .iter()
+        .skip_while(|c| !Rc::ptr_eq(c, node))
+        .skip(1)
+        .cloned()
+        .collect()
+}
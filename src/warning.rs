@@ -0,0 +1,68 @@
+use std::fmt;
+
+/// Category of a [`Warning`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WarningKind {
+    /// `v-else` or `v-else-if` without a preceding `v-if`; the element is kept
+    OrphanElse,
+    /// `v-bind` object syntax with a value that isn't an object; the attribute is kept
+    InvalidBindObject,
+    /// Expression that failed to evaluate, such as one reading an undefined variable
+    EvalError,
+    /// Attribute repeated on an element; the first one is kept
+    DuplicateAttribute,
+    /// `v-if` and `v-for` on the same element; `v-if` is evaluated first
+    IfWithFor,
+    /// `$t` or `$tc` key without a message; the key is rendered
+    MissingMessage,
+    /// `<component :is>` naming neither a registered component nor a tag; the element is removed
+    UnknownComponent,
+}
+
+/// Problem found while rendering that doesn't stop the render
+///
+/// # Examples
+///
+/// ```
+/// use prevue::{WarningKind, render_with_warnings};
+/// use serde_json::json;
+///
+/// let html = "<p>\n  <span v-else>orphan</span>\n</p>";
+/// let (_, warnings) = render_with_warnings(html.to_string(), json!({})).unwrap();
+/// assert_eq!(warnings[0].kind, WarningKind::OrphanElse);
+/// assert_eq!(warnings[0].line, Some(2));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Warning {
+    /// Category of the problem
+    pub kind: WarningKind,
+    /// Name of the template the problem is in, when it was loaded by name, like an include
+    pub file: Option<String>,
+    /// 1-based line in the template source, if known
+    pub line: Option<usize>,
+    /// Description of the problem
+    pub message: String,
+}
+
+impl Warning {
+    pub(crate) fn new(kind: WarningKind, line: Option<usize>, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            file: None,
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{} at {}:{}", self.message, file, line),
+            (Some(file), None) => write!(f, "{} in {}", self.message, file),
+            (None, Some(line)) => write!(f, "{} at line {}", self.message, line),
+            (None, None) => write!(f, "{}", self.message),
+        }
+    }
+}
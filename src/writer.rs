@@ -0,0 +1,68 @@
+use std::{fmt, io, str};
+
+// Adapter that lets the byte-oriented serializer write into a `fmt::Write` sink.
+// Multi-byte characters split across writes are held back until complete.
+pub(crate) struct FmtWriter<'a, W: fmt::Write> {
+    inner: &'a mut W,
+    pending: Vec<u8>,
+}
+
+impl<'a, W: fmt::Write> FmtWriter<'a, W> {
+    pub fn new(inner: &'a mut W) -> Self {
+        Self {
+            inner,
+            pending: Vec::new(),
+        }
+    }
+
+    // Fail if the output ended in the middle of a character
+    pub fn finish(self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "incomplete UTF-8 sequence at end of output",
+            ))
+        }
+    }
+
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.inner.write_str(s).map_err(io::Error::other)
+    }
+}
+
+impl<W: fmt::Write> io::Write for FmtWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.pending.is_empty()
+            && let Ok(s) = str::from_utf8(buf)
+        {
+            self.write_str(s)?;
+            return Ok(buf.len());
+        }
+
+        self.pending.extend_from_slice(buf);
+        let pending = std::mem::take(&mut self.pending);
+        let valid_up_to = match str::from_utf8(&pending) {
+            Ok(s) => {
+                self.write_str(s)?;
+                return Ok(buf.len());
+            }
+            Err(e) if e.error_len().is_some() => {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, e));
+            }
+            Err(e) => e.valid_up_to(),
+        };
+
+        let (valid, rest) = pending.split_at(valid_up_to);
+        if let Ok(s) = str::from_utf8(valid) {
+            self.write_str(s)?;
+        }
+        self.pending = rest.to_vec();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -221,6 +221,77 @@ fn test_bind_false_kept() {
     assert_eq!(output, expected);
 }
 
+// === Boolean & Enumerated Attributes ===
+
+#[test]
+fn test_bind_boolean_attributes() {
+    // Boolean attributes are dropped when falsy and rendered empty when truthy
+    let input = r#"
+    <div>
+        <input :disabled="false" :checked="true" :required="value" :hidden="null" :readonly="''">
+    </div>
+    "#;
+    let output = render(input.to_string(), data()).unwrap();
+
+    let expected = r#"<html><head></head><body><div>
+        <input checked="" required="" readonly="">
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_bind_enumerated_attributes() {
+    // Enumerated attributes are coerced to "true" or "false"
+    let input = r#"
+    <div>
+        <p :draggable="false" :spellcheck="value" :contenteditable="'plaintext-only'">a</p>
+        <p :draggable="null" :spellcheck="'false'" :contenteditable="1">b</p>
+    </div>
+    "#;
+    let output = render(input.to_string(), data()).unwrap();
+
+    let expected = r#"<html><head></head><body><div>
+        <p draggable="false" spellcheck="true" contenteditable="plaintext-only">a</p>
+        <p draggable="false" spellcheck="false" contenteditable="true">b</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_bind_aria_attributes() {
+    // aria-* attributes keep their string values
+    let input = r#"
+    <div>
+        <button :aria-hidden="false" :aria-expanded="true" :aria-label="id">x</button>
+    </div>
+    "#;
+    let output = render(input.to_string(), data()).unwrap();
+
+    let expected = r#"<html><head></head><body><div>
+        <button aria-hidden="false" aria-expanded="true" aria-label="title">x</button>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_bind_object_boolean_attributes() {
+    let input = r#"
+    <div>
+        <input v-bind="{ disabled: false, checked: 1, draggable: true, 'aria-busy': false }">
+    </div>
+    "#;
+    let output = render(input.to_string(), data()).unwrap();
+
+    let expected = r#"<html><head></head><body><div>
+        <input checked="" draggable="true" aria-busy="false">
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
 // === Object Syntax ===
 
 #[test]
@@ -256,3 +327,25 @@ fn test_bind_object_overrides_existing_attr() {
     </body></html>"#;
     assert_eq!(output, expected);
 }
+
+// === Reserved Attributes ===
+
+#[test]
+fn test_bind_key_and_ref_stripped() {
+    // key and ref are virtual DOM hints and never reach the output
+    let input = r#"
+    <ul>
+        <li v-for="n in 2" :key="n" ref="item" ref_for>{{ n }}</li>
+        <li key="static" :ref="id" v-bind="{ key: 1, title: id }">x</li>
+    </ul>
+    "#;
+    let output = render(input.to_string(), data()).unwrap();
+
+    let expected = r#"<html><head></head><body><ul>
+        <li>1</li>
+        <li>2</li>
+        <li title="title">x</li>
+    </ul>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
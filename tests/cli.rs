@@ -0,0 +1,230 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+// Directory of template and data files, removed when dropped
+struct Dir(PathBuf);
+
+impl Dir {
+    fn new(name: &str, files: &[(&str, &str)]) -> Self {
+        let dir = std::env::temp_dir().join(format!("prevue-cli-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for (file, contents) in files {
+            fs::write(dir.join(file), contents).unwrap();
+        }
+        Self(dir)
+    }
+
+    fn path(&self, file: &str) -> String {
+        self.0.join(file).to_string_lossy().into_owned()
+    }
+}
+
+impl Drop for Dir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn prevue(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_prevue"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+// === Data ===
+
+#[test]
+fn test_cli_data_formats() {
+    let dir = Dir::new(
+        "formats",
+        &[
+            (
+                "page.html",
+                r#"<p v-for="tag in tags">{{ title }}: {{ tag }}</p>"#,
+            ),
+            ("data.json", r#"{ "title": "JSON", "tags": ["a"] }"#),
+            ("data.yaml", "title: YAML\ntags:\n  - b\n"),
+            ("data.toml", "title = \"TOML\"\ntags = [\"c\"]\n"),
+        ],
+    );
+    let page = dir.path("page.html");
+
+    for (data, expected) in [
+        ("data.json", "<p>JSON: a</p>"),
+        ("data.yaml", "<p>YAML: b</p>"),
+        ("data.toml", "<p>TOML: c</p>"),
+    ] {
+        let output = prevue(&[&page, "--data", &dir.path(data), "--fragment"], "");
+        assert!(output.status.success(), "{}", stderr(&output));
+        assert_eq!(stdout(&output), expected);
+    }
+}
+
+#[test]
+fn test_cli_stdin() {
+    let dir = Dir::new("stdin", &[("page.html", "<p>{{ title }}</p>")]);
+    let page = dir.path("page.html");
+
+    let output = prevue(&[&page, "-d", "-"], r#"{ "title": "Hello" }"#);
+    assert_eq!(
+        stdout(&output),
+        "<html><head></head><body><p>Hello</p></body></html>"
+    );
+
+    let output = prevue(
+        &[&page, "-d", "-", "--format=yaml", "--fragment"],
+        "title: Hi",
+    );
+    assert_eq!(stdout(&output), "<p>Hi</p>");
+}
+
+// === Output ===
+
+#[test]
+fn test_cli_output_file() {
+    let dir = Dir::new(
+        "output",
+        &[
+            (
+                "page.html",
+                "<ul>\n<include src=\"item.html\"></include>\n</ul>",
+            ),
+            ("item.html", "<li>{{ 1 + 1 }}</li>"),
+        ],
+    );
+    let out = dir.path("out.html");
+
+    let output = prevue(
+        &[&dir.path("page.html"), "-o", &out, "--whitespace", "minify"],
+        "",
+    );
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        fs::read_to_string(&out).unwrap(),
+        "<html><head></head><body><ul><li>2</li></ul></body></html>"
+    );
+}
+
+// === Sites ===
+
+#[test]
+fn test_cli_build_fragment() {
+    let dir = Dir::new("build", &[]);
+    fs::create_dir_all(dir.0.join("site/pages")).unwrap();
+    fs::write(dir.0.join("site/pages/index.html"), "<p>{{ 1 + 1 }}</p>").unwrap();
+    let out = dir.path("out");
+
+    let output = prevue(&["build", &dir.path("site"), "-o", &out, "--fragment"], "");
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(dir.0.join("out/index.html")).unwrap(),
+        "<p>2</p>"
+    );
+}
+
+// === Errors ===
+
+#[test]
+fn test_cli_template_error() {
+    let dir = Dir::new("error", &[("page.html", "<div>\n  {{ unclosed }\n</div>")]);
+    let page = dir.path("page.html");
+
+    let output = prevue(&[&page], "");
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        format!(
+            "error: unterminated interpolation `{{{{ unclosed }}`\n --> {page}:2\n  |\n2 |   {{{{ unclosed }}\n  |   ^^^^^^^^^^^^^\n"
+        )
+    );
+}
+
+#[test]
+fn test_cli_strict() {
+    let dir = Dir::new(
+        "strict",
+        &[("page.html", "<p>\n  <span v-else>orphan</span>\n</p>")],
+    );
+    let page = dir.path("page.html");
+
+    let output = prevue(&[&page], "");
+    assert!(output.status.success());
+    assert!(stderr(&output).starts_with("warning: `v-else` has no preceding `v-if`"));
+
+    let output = prevue(&[&page, "--strict"], "");
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).starts_with("error: `v-else` has no preceding `v-if`"));
+    assert!(stderr(&output).ends_with("error: 1 warning in strict mode\n"));
+}
+
+#[test]
+fn test_cli_include_warning() {
+    // Warnings of an include point into its file
+    let dir = Dir::new(
+        "include-warning",
+        &[
+            (
+                "page.html",
+                r#"<main><include src="card.html"></include></main>"#,
+            ),
+            ("card.html", "<div>\n  <span v-else>orphan</span>\n</div>"),
+        ],
+    );
+    let card = dir.path("card.html");
+
+    let output = prevue(&[&dir.path("page.html")], "");
+    assert!(output.status.success());
+    assert!(stderr(&output).starts_with(&format!(
+        "warning: `v-else` has no preceding `v-if`; the element is kept\n --> {card}:2\n"
+    )));
+}
+
+#[test]
+fn test_cli_usage() {
+    let output = prevue(&["--whitespace", "tidy", "page.html"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("error: unknown whitespace mode `tidy`\n\nUsage: prevue"));
+
+    // Options of the other mode are rejected rather than ignored
+    let output = prevue(&["build", "site", "-o", "out", "--strict"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("error: `--strict` can't be used with `build`\n"));
+
+    let output = prevue(&["build", "site", "-o", "out", "--data", "data.json"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("error: `--data` can't be used with `build`\n"));
+
+    let output = prevue(&["page.html", "--layout", "base.html"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("error: `--layout` can't be used without `build`\n"));
+
+    let output = prevue(&["--help"], "");
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("Usage: prevue [OPTIONS] <TEMPLATE>"));
+}
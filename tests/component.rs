@@ -0,0 +1,251 @@
+use prevue::{Component, Options, Style, TemplateError};
+use serde_json::json;
+
+const SOURCE: &str = r#"<!-- A product card -->
+<template>
+  <div class="card">
+    <h2>{{ title }}</h2>
+    <p>{{ formatted }} x {{ quantity }} = {{ total }}</p>
+    <template v-if="onSale">
+      <span>{{ label("sale") }}</span>
+    </template>
+  </div>
+</template>
+
+<script>
+export default {
+  props: {
+    title: { type: String, default: "Untitled" },
+    quantity: { type: Number, default: 1 },
+    tags: { type: Array, default: () => ["new"] },
+  },
+  data() {
+    return { onSale: this.price < 10 };
+  },
+  computed: {
+    total() {
+      return this.price * this.quantity;
+    },
+    formatted: {
+      get() {
+        return "$" + this.price;
+      },
+    },
+  },
+  methods: {
+    label(text) {
+      return text.toUpperCase() + " " + this.tags.join(",");
+    },
+  },
+};
+</script>
+
+<style scoped>
+.card { color: red; }
+</style>
+
+<style lang="scss">
+body { margin: 0; }
+</style>
+
+<docs>
+Custom blocks are <em>ignored</em>.
+</docs>
+"#;
+
+// === Render ===
+
+#[test]
+fn test_component_render() {
+    let component = Component::parse(SOURCE).unwrap();
+    let output = component
+        .render(json!({ "price": 5, "quantity": 3 }))
+        .unwrap();
+
+    let expected = r#"<html><head></head><body><div class="card" data-v-8873725c="">
+    <h2 data-v-8873725c="">Untitled</h2>
+    <p data-v-8873725c="">$5 x 3 = 15</p>
+    <span data-v-8873725c="">SALE new</span>
+  </div>
+</body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_component_props_over_defaults() {
+    let component = Component::parse(SOURCE).unwrap();
+    let output = component
+        .render(json!({ "title": "Pen", "price": 20, "tags": ["a", "b"] }))
+        .unwrap();
+
+    let expected = r#"<html><head></head><body><div class="card" data-v-8873725c="">
+    <h2 data-v-8873725c="">Pen</h2>
+    <p data-v-8873725c="">$20 x 1 = 20</p>
+  </div>
+</body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_component_sandbox() {
+    let component = Component::parse(SOURCE).unwrap();
+    let output = Options::new()
+        .sandbox(true)
+        .render_component(&component, json!({ "price": 2 }))
+        .unwrap();
+
+    assert!(output.contains(r#"<p data-v-8873725c="">$2 x 1 = 2</p>"#));
+    assert!(output.contains(r#"<span data-v-8873725c="">SALE new</span>"#));
+}
+
+#[test]
+fn test_component_without_script() {
+    let source = "<template><p>{{ message }}</p></template>";
+    let component = Component::parse(source).unwrap();
+    let output = component.render(json!({ "message": "Hi" })).unwrap();
+
+    assert_eq!(output, "<html><head></head><body><p>Hi</p></body></html>");
+}
+
+// === Blocks ===
+
+#[test]
+fn test_component_blocks() {
+    let component = Component::parse(SOURCE).unwrap();
+
+    assert!(component.template().starts_with("\n  <div class=\"card\">"));
+    assert!(component.template().ends_with("</div>\n"));
+    assert_eq!(
+        component.styles(),
+        &[
+            Style {
+                css: "\n.card { color: red; }\n".to_string(),
+                scoped: true,
+                lang: None,
+            },
+            Style {
+                css: "\nbody { margin: 0; }\n".to_string(),
+                scoped: false,
+                lang: Some("scss".to_string()),
+            },
+        ]
+    );
+}
+
+// === Scoped styles ===
+
+#[test]
+fn test_component_css() {
+    // Preprocessor blocks are left out of the style sheet
+    let component = Component::parse(SOURCE).unwrap();
+
+    assert_eq!(component.scope_id(), Some("data-v-8873725c"));
+    assert_eq!(component.css(), ".card[data-v-8873725c] { color: red; }\n");
+}
+
+#[test]
+fn test_component_css_selectors() {
+    let source = r#"<template><p>Hi</p></template>
+<style scoped>
+.a, .b > .c { color: red }
+.d:hover::before { content: "}" }
+.e :deep(.f) { margin: 0 }
+:global(.g) { padding: 0 }
+@media (min-width: 600px) { .h { display: none } }
+@keyframes spin { from { opacity: 0 } to { opacity: 1 } }
+input[type="text"] { border: 0 }
+</style>
+<style>
+.i { color: blue }
+</style>"#;
+    let component = Component::parse(source).unwrap();
+    let scope = component.scope_id().unwrap();
+
+    let expected = r#".a[S], .b > .c[S] { color: red }
+.d[S]:hover::before { content: "}" }
+.e[S] .f { margin: 0 }
+.g { padding: 0 }
+@media (min-width: 600px) { .h[S] { display: none } }
+@keyframes spin { from { opacity: 0 } to { opacity: 1 } }
+input[type="text"][S] { border: 0 }
+.i { color: blue }
+"#;
+    assert_eq!(component.css(), expected.replace('S', scope));
+}
+
+#[test]
+fn test_component_inject_styles() {
+    let component = Component::parse(SOURCE).unwrap();
+    let output = Options::new()
+        .inject_styles(true)
+        .render_component(&component, json!({ "price": 20 }))
+        .unwrap();
+
+    assert!(output.starts_with(
+        "<html><head><style>.card[data-v-8873725c] { color: red; }\n</style></head><body>"
+    ));
+}
+
+#[test]
+fn test_component_unscoped() {
+    let source = "<template><p>Hi</p></template><style>p { color: red; }</style>";
+    let component = Component::parse(source).unwrap();
+    let output = component.render(json!({})).unwrap();
+
+    assert_eq!(component.scope_id(), None);
+    assert_eq!(component.css(), "p { color: red; }\n");
+    assert_eq!(output, "<html><head></head><body><p>Hi</p></body></html>");
+}
+
+// === Errors ===
+
+fn parse_error(source: &str) -> TemplateError {
+    Component::parse(source)
+        .unwrap_err()
+        .downcast::<TemplateError>()
+        .unwrap()
+}
+
+#[test]
+fn test_component_errors() {
+    assert_eq!(
+        parse_error("<script>\nexport default {};\n</script>"),
+        TemplateError {
+            file: None,
+            line: None,
+            message: "component has no <template> block".to_string(),
+        }
+    );
+    assert_eq!(
+        parse_error("<template>\n<p></p>\n<script setup>\n</script>\n</template>\n<script setup>\nconst a = 1;\n</script>").line,
+        Some(6)
+    );
+    assert_eq!(
+        parse_error(
+            "<template></template>\n<script>\n\nimport x from 'x';\nexport default {};\n</script>"
+        ),
+        TemplateError {
+            file: None,
+            line: Some(4),
+            message: "imports are not supported in <script>".to_string(),
+        }
+    );
+    assert_eq!(
+        parse_error("<template></template>\n<script>\nconst a = 1;\n</script>").message,
+        "<script> must define the component with `export default`"
+    );
+    assert_eq!(
+        parse_error("\n<template>\n<p>open").message,
+        "<template> block is not closed"
+    );
+}
+
+#[test]
+fn test_component_template_error_line() {
+    // Template lines are reported in the component source
+    let source = "<!-- header -->\n\n<template>\n  <p>\n    {{ unclosed }\n  </p>\n</template>";
+    let component = Component::parse(source).unwrap();
+    let error = component.render(json!({})).unwrap_err();
+
+    assert_eq!(error.downcast_ref::<TemplateError>().unwrap().line, Some(5));
+}
@@ -0,0 +1,184 @@
+use prevue::Options;
+use serde_json::{Value, json};
+
+const SCRIPT: &str = r#"(() => {
+    let calls = 0;
+    return {
+        computed: {
+            fullName() {
+                return `${this.first} ${this.last}`;
+            },
+            total() {
+                calls += 1;
+                return this.items.reduce((sum, item) => sum + item.price * item.quantity, 0);
+            },
+            broken() {
+                throw new Error("never read");
+            },
+        },
+        methods: {
+            subtotal(item) {
+                return this.currency + (item.price * item.quantity).toFixed(2);
+            },
+            calls() {
+                return calls;
+            },
+        },
+    };
+})()"#;
+
+fn data() -> Value {
+    json!({
+        "first": "Ada",
+        "last": "Lovelace",
+        "currency": "$",
+        "items": [
+            { "name": "pen", "price": 1.5, "quantity": 4 },
+            { "name": "ink", "price": 7, "quantity": 1 },
+        ],
+    })
+}
+
+fn render(input: &str) -> String {
+    Options::new()
+        .script(SCRIPT)
+        .render(input.to_string(), data())
+        .unwrap()
+}
+
+// === Computed ===
+
+#[test]
+fn test_computed() {
+    let input = r#"
+    <div :title="fullName">
+        <p>{{ fullName }}</p>
+        <p v-if="total > 10">{{ total }}</p>
+    </div>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><div title="Ada Lovelace">
+        <p>Ada Lovelace</p>
+        <p>13</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_computed_memoized() {
+    // Getters run once per render, on first access
+    let input = r#"<p>{{ total }} {{ total }} {{ calls() }}</p>"#;
+    let options = Options::new().script(SCRIPT);
+    let first = options.render(input.to_string(), data()).unwrap();
+    let second = options
+        .render(
+            input.to_string(),
+            json!({ "items": [{ "price": 2, "quantity": 2 }] }),
+        )
+        .unwrap();
+
+    assert_eq!(
+        first,
+        "<html><head></head><body><p>13 13 1</p></body></html>"
+    );
+    assert_eq!(
+        second,
+        "<html><head></head><body><p>4 4 1</p></body></html>"
+    );
+}
+
+#[test]
+fn test_computed_lazy() {
+    // Getters that are never read never run
+    let input = r#"<p>{{ calls() }}</p>"#;
+    let (output, warnings) = Options::new()
+        .script(SCRIPT)
+        .render_with_warnings(input.to_string(), data())
+        .unwrap();
+
+    assert_eq!(output, "<html><head></head><body><p>0</p></body></html>");
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn test_computed_data_precedence() {
+    let input = r#"<p>{{ fullName }}</p>"#;
+    let output = Options::new()
+        .script(SCRIPT)
+        .render(input.to_string(), json!({ "fullName": "Grace Hopper" }))
+        .unwrap();
+
+    assert_eq!(
+        output,
+        "<html><head></head><body><p>Grace Hopper</p></body></html>"
+    );
+}
+
+// === Methods ===
+
+#[test]
+fn test_computed_methods() {
+    let input = r#"
+    <ul>
+        <li v-for="item in items" :data-total="subtotal(item)">{{ item.name }}: {{ subtotal(item) }}</li>
+    </ul>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><ul>
+        <li data-total="$6.00">pen: $6.00</li>
+        <li data-total="$7.00">ink: $7.00</li>
+    </ul>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_computed_export_default() {
+    let input = r#"<p>{{ double(count) }}</p>"#;
+    let output = Options::new()
+        .script("export default { methods: { double(n) { return n * 2; } } };")
+        .render(input.to_string(), json!({ "count": 21 }))
+        .unwrap();
+
+    assert_eq!(output, "<html><head></head><body><p>42</p></body></html>");
+}
+
+// === This ===
+
+#[test]
+fn test_computed_this() {
+    // `this` is the data in every binding, including inside loops
+    let input = r#"
+    <ul :title="this.first">
+        <li v-for="item in items">{{ this.last }} {{ this.fullName }} {{ item.name }}</li>
+    </ul>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><ul title="Ada">
+        <li>Lovelace Ada Lovelace pen</li>
+        <li>Lovelace Ada Lovelace ink</li>
+    </ul>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+// === Sandbox ===
+
+#[test]
+fn test_computed_sandbox() {
+    let input = r#"<p>{{ fullName }}: {{ total }} {{ total }} {{ calls() }}</p>"#;
+    let output = Options::new()
+        .sandbox(true)
+        .script(SCRIPT)
+        .render(input.to_string(), data())
+        .unwrap();
+
+    assert_eq!(
+        output,
+        "<html><head></head><body><p>Ada Lovelace: 13 13 1</p></body></html>"
+    );
+}
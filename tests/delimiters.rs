@@ -0,0 +1,70 @@
+use prevue::Options;
+use serde_json::{Value, json};
+
+fn data() -> Value {
+    json!({
+        "message": "Hello",
+        "user": { "name": "Alice" },
+    })
+}
+
+fn render(input: &str, open: &str, close: &str) -> String {
+    Options::new()
+        .delimiters(open, close)
+        .render(input.to_string(), data())
+        .unwrap()
+}
+
+#[test]
+fn test_delimiters_custom() {
+    let input = r#"
+    <div>
+        <p>${ message }, ${user.name}!</p>
+        <p>{{ message }}</p>
+    </div>
+    "#;
+    let output = render(input, "${", "}");
+
+    let expected = r#"<html><head></head><body><div>
+        <p>Hello, Alice!</p>
+        <p>{{ message }}</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_delimiters_regex_characters() {
+    // Delimiters are matched literally
+    let input = r#"<p>[[ message ]] (( message ))</p>"#;
+    let output = render(input, "[[", "]]");
+
+    let expected = "<html><head></head><body><p>Hello (( message ))</p></body></html>";
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_delimiters_pre() {
+    let input = r#"
+    <div>
+        <p v-pre>${ message }</p>
+        <p>${ message }</p>
+    </div>
+    "#;
+    let output = render(input, "${", "}");
+
+    let expected = r#"<html><head></head><body><div>
+        <p>${ message }</p>
+        <p>Hello</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_delimiters_empty() {
+    let result = Options::new()
+        .delimiters("", "}")
+        .render("<p></p>".to_string(), data());
+    assert!(result.is_err());
+}
@@ -0,0 +1,141 @@
+use prevue::Options;
+use serde_json::{Value, json};
+use std::time::{Duration, UNIX_EPOCH};
+
+fn data() -> Value {
+    json!({
+        "price": 1234.5,
+        "names": ["b", "a"],
+    })
+}
+
+fn render(input: &str, options: Options) -> String {
+    options.render(input.to_string(), data()).unwrap()
+}
+
+// === Clock ===
+
+#[test]
+fn test_deterministic_now() {
+    let input = r#"
+    <div>
+        <p>{{ new Date().toISOString() }}</p>
+        <p>{{ Date.now() }}</p>
+        <p>{{ new Date(0).toISOString() }}</p>
+        <p>{{ new Date() instanceof Date }}</p>
+    </div>
+    "#;
+    let options = Options::new().now(UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+    let output = render(input, options);
+
+    let expected = r#"<html><head></head><body><div>
+        <p>2023-11-14T22:13:20.000Z</p>
+        <p>1700000000000</p>
+        <p>1970-01-01T00:00:00.000Z</p>
+        <p>true</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_deterministic_now_before_epoch() {
+    let input = r#"<p>{{ new Date().toISOString() }}</p>"#;
+    let options = Options::new().now(UNIX_EPOCH - Duration::from_secs(86_400));
+    let output = render(input, options);
+
+    let expected = "<html><head></head><body><p>1969-12-31T00:00:00.000Z</p></body></html>";
+    assert_eq!(output, expected);
+}
+
+// === Random ===
+
+#[test]
+fn test_deterministic_seed() {
+    let input = r#"<p v-for="_ in 3">{{ Math.random() }}</p>"#;
+    let output = render(input, Options::new().seed(42));
+
+    let expected = "<html><head></head><body><p>0.6011037519201636</p><p>0.44829055899754167</p><p>0.8524657934904099</p></body></html>";
+    assert_eq!(output, expected);
+    assert_eq!(render(input, Options::new().seed(42)), expected);
+}
+
+// === Locale ===
+
+#[test]
+fn test_deterministic_locale() {
+    // Boa resolves `de-DE` to its language
+    let input = r#"
+    <div>
+        <p>{{ price.toLocaleString() }}</p>
+        <p>{{ price.toLocaleString("en-US") }}</p>
+        <p>{{ [price].toLocaleString() }}</p>
+        <p>{{ new Intl.NumberFormat().resolvedOptions().locale }}</p>
+    </div>
+    "#;
+    let output = render(input, Options::new().locale("de-DE"));
+
+    let expected = r#"<html><head></head><body><div>
+        <p>1.234,5</p>
+        <p>1,234.5</p>
+        <p>1.234,5</p>
+        <p>de</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+// === Time zone ===
+
+#[test]
+fn test_deterministic_time_zone() {
+    let input = r#"
+    <div>
+        <p>{{ new Date(0).getHours() }}:{{ new Date(0).getMinutes() }}</p>
+        <p>{{ new Date(0).getTimezoneOffset() }}</p>
+        <p>{{ new Date(0).toString() }}</p>
+    </div>
+    "#;
+    let output = render(input, Options::new().time_zone("+09:30"));
+
+    let expected = r#"<html><head></head><body><div>
+        <p>9:30</p>
+        <p>-570</p>
+        <p>Thu Jan 01 1970 09:30:00 GMT+0930</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+
+    let output = render(input, Options::new().time_zone("UTC"));
+
+    let expected = r#"<html><head></head><body><div>
+        <p>0:0</p>
+        <p>0</p>
+        <p>Thu Jan 01 1970 00:00:00 GMT+0000</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_deterministic_time_zone_unsupported() {
+    let result = Options::new()
+        .time_zone("Asia/Seoul")
+        .render("<p></p>".to_string(), data());
+
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "unsupported time zone `Asia/Seoul`, expected `UTC` or an offset like `+09:00`"
+    );
+}
+
+#[test]
+fn test_deterministic_sandbox() {
+    // The environment is pinned before the sandbox freezes it
+    let input = r#"<p>{{ Date.now() }} {{ Math.random() }}</p>"#;
+    let options = Options::new().sandbox(true).now(UNIX_EPOCH).seed(42);
+    let output = render(input, options);
+
+    let expected = "<html><head></head><body><p>0 0.6011037519201636</p></body></html>";
+    assert_eq!(output, expected);
+}
@@ -0,0 +1,190 @@
+use prevue::{Component, Options, TemplateError, WarningKind};
+use serde_json::{Value, json};
+
+fn data() -> Value {
+    json!({
+        "kind": "card",
+        "title": "Hello",
+        "body": "World",
+        "price": 3.5,
+        "items": [
+            { "kind": "fancy-item", "label": "A" },
+            { "kind": "li", "label": "B" },
+        ],
+    })
+}
+
+fn options() -> Options {
+    let card = r#"<template><article><h2>{{ title }} #{{ dataId }} {{ typeof body }}</h2><slot>Empty</slot></article></template>"#;
+    let fancy = r#"<template><li class="fancy">{{ label }}</li></template>"#;
+    let price = r#"<template><span>{{ formatted }}</span></template>
+<script>
+export default {
+  props: { amount: { default: 0 } },
+  computed: {
+    formatted() {
+      return "$" + this.amount.toFixed(2);
+    },
+  },
+};
+</script>"#;
+    Options::new()
+        .component("card", Component::parse(card).unwrap())
+        .component("FancyItem", Component::parse(fancy).unwrap())
+        .component("Price", Component::parse(price).unwrap())
+}
+
+fn render(input: &str) -> String {
+    options().render(input.to_string(), data()).unwrap()
+}
+
+// === Components ===
+
+#[test]
+fn test_dynamic_component() {
+    // Attributes are props, children fill the slot and the component sees only its props
+    let input = r#"
+    <div>
+        <component :is="kind" :title="title" data-id="7">
+            <p>{{ body }}</p>
+        </component>
+    </div>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><div>
+        <article><h2>Hello #7 undefined</h2>
+            <p>World</p>
+        </article>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_dynamic_component_slot_fallback() {
+    let input = r#"<component is="card" title="Empty"></component>"#;
+    let output = render(input);
+
+    let expected =
+        "<html><head></head><body><article><h2>Empty # undefined</h2>Empty</article></body></html>";
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_dynamic_component_script() {
+    let input = r#"<p><component is="Price" :amount="price"></component></p>"#;
+    let output = render(input);
+
+    let expected = "<html><head></head><body><p><span>$3.50</span></p></body></html>";
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_dynamic_component_sandbox() {
+    let input = r#"<p><component is="Price" :amount="price"></component></p>"#;
+    let output = options()
+        .sandbox(true)
+        .render(input.to_string(), data())
+        .unwrap();
+
+    let expected = "<html><head></head><body><p><span>$3.50</span></p></body></html>";
+    assert_eq!(output, expected);
+}
+
+// === Tags ===
+
+#[test]
+fn test_dynamic_tag() {
+    let input = r#"
+    <div>
+        <component :is="'section'" class="box" :title="title">{{ body }}</component>
+    </div>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><div>
+        <section class="box" title="Hello">World</section>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_dynamic_for() {
+    // Kebab-case names find PascalCase components, other names are tags
+    let input = r#"
+    <ul>
+        <component v-for="item in items" :is="item.kind" :label="item.label"></component>
+    </ul>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><ul>
+        <li class="fancy">A</li>
+        <li label="B"></li>
+    </ul>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+// === Styles ===
+
+#[test]
+fn test_dynamic_scoped_styles() {
+    // Elements of the component carry its attribute; the slot content belongs to the page
+    let source = r#"<template><p class="note"><slot></slot></p></template>
+<style scoped>
+.note { color: red; }
+</style>"#;
+    let note = Component::parse(source).unwrap();
+    let scope = note.scope_id().unwrap().to_string();
+    let input = r#"<component is="note"><b>{{ body }}</b></component>"#;
+    let output = Options::new()
+        .component("note", note)
+        .inject_styles(true)
+        .render(input.to_string(), data())
+        .unwrap();
+
+    let expected = format!(
+        r#"<html><head><style>.note[{scope}] {{ color: red; }}
+</style></head><body><p class="note" {scope}=""><b>World</b></p></body></html>"#
+    );
+    assert_eq!(output, expected);
+}
+
+// === Errors ===
+
+#[test]
+fn test_dynamic_missing() {
+    let input = "<div>\n<component :is=\"null\"></component>\n<component is=\"not a tag\"></component>\n</div>";
+    let (output, warnings) = render_with_warnings(input);
+
+    assert_eq!(
+        output,
+        "<html><head></head><body><div>\n</div></body></html>"
+    );
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::UnknownComponent);
+    assert_eq!(warnings[0].line, Some(3));
+}
+
+#[test]
+fn test_dynamic_recursion() {
+    let source = r#"<template><div><component is="loop"></component></div></template>"#;
+    let error = Options::new()
+        .component("loop", Component::parse(source).unwrap())
+        .render(r#"<component is="loop"></component>"#.to_string(), data())
+        .unwrap_err();
+
+    assert_eq!(
+        error.downcast_ref::<TemplateError>().unwrap().message,
+        "component `loop` is nested more than 64 levels deep"
+    );
+}
+
+fn render_with_warnings(input: &str) -> (String, Vec<prevue::Warning>) {
+    options()
+        .render_with_warnings(input.to_string(), data())
+        .unwrap()
+}
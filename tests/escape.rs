@@ -0,0 +1,147 @@
+use prevue::render;
+use serde_json::{Value, json};
+
+fn data() -> Value {
+    json!({
+        "title": "</script><script>alert(1)</script>",
+        "user": {
+            "name": "Alice <admin>",
+            "site": "https://example.com",
+        },
+        "links": [
+            "javascript:alert(1)",
+            " JaVa\tScRiPt:alert(1)",
+            "vbscript:msgbox",
+            "data:text/html,<script>alert(1)</script>",
+            "data:image/png;base64,AAAA",
+            "/relative?q=1",
+        ],
+        "color": "red; } body { display: none",
+    })
+}
+
+// === URL Attributes ===
+
+#[test]
+fn test_escape_url_schemes() {
+    let input = r#"
+    <div>
+        <a v-for="link in links" :href="link">x</a>
+    </div>
+    "#;
+    let output = render(input.to_string(), data()).unwrap();
+
+    let expected = r#"<html><head></head><body><div>
+        <a href="about:invalid#blocked">x</a>
+        <a href="about:invalid#blocked">x</a>
+        <a href="about:invalid#blocked">x</a>
+        <a href="about:invalid#blocked">x</a>
+        <a href="data:image/png;base64,AAAA">x</a>
+        <a href="/relative?q=1">x</a>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_escape_url_attributes() {
+    // Bound src, action and formaction are checked as well, in both binding syntaxes;
+    // static attributes are left to the template author
+    let input = r#"
+    <form :action="links[0]">
+        <img :src="links[3]">
+        <button v-bind="{ formaction: links[2] }">go</button>
+        <a :href="user.site" title="javascript:">site</a>
+    </form>
+    "#;
+    let output = render(input.to_string(), data()).unwrap();
+
+    let expected = r#"<html><head></head><body><form action="about:invalid#blocked">
+        <img src="about:invalid#blocked">
+        <button formaction="about:invalid#blocked">go</button>
+        <a href="https://example.com" title="javascript:">site</a>
+    </form>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+// === Script ===
+
+#[test]
+fn test_escape_script_json() {
+    let input =
+        r#"<script type="application/json">{"user": {{ user }}, "title": {{ title }}}</script>"#;
+    let output = render(input.to_string(), data()).unwrap();
+
+    let expected = r#"<html><head><script type="application/json">{"user": {"name":"Alice \u003cadmin\u003e","site":"https://example.com"}, "title": "\u003c/script\u003e\u003cscript\u003ealert(1)\u003c/script\u003e"}</script></head><body></body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_escape_script() {
+    let input = r#"<script>var title = {{ title }};</script>"#;
+    let output = render(input.to_string(), data()).unwrap();
+
+    let expected = r#"<html><head><script>var title = "\u003c/script\u003e\u003cscript\u003ealert(1)\u003c/script\u003e";</script></head><body></body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_escape_script_literal() {
+    // Objects and arrays are written as JavaScript literals, strings with their quotes
+    let input = r#"<script>var user = {{ user }}; var list = {{ list }}; var quote = {{ quote }};</script>"#;
+    let data = json!({
+        "user": { "name": "Alice", "admin": false },
+        "list": [1, "two", null],
+        "quote": "\\\"; alert(document.cookie); //\n'",
+    });
+    let output = render(input.to_string(), data).unwrap();
+
+    let expected = r#"<html><head><script>var user = {"name":"Alice","admin":false}; var list = [1,"two",null]; var quote = "\\\"; alert(document.cookie); //\n'";</script></head><body></body></html>"#;
+    assert_eq!(output, expected);
+}
+
+// === Style ===
+
+#[test]
+fn test_escape_style() {
+    let input = r#"<style>p { color: {{ color }}; }</style>"#;
+    let output = render(input.to_string(), data()).unwrap();
+
+    let expected = r#"<html><head><style>p { color: red\3b  \7d  body \7b  display: none; }</style></head><body></body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_escape_style_quotes() {
+    // Quoted strings are kept, only characters that could end the declaration are escaped
+    let input = r#"<style>p { font-family: {{ font }}; background: {{ image }}; }</style>"#;
+    let data = json!({
+        "font": "\"Open Sans\", sans-serif",
+        "image": "url('/a.png')",
+    });
+    let output = render(input.to_string(), data).unwrap();
+
+    let expected = r#"<html><head><style>p { font-family: "Open Sans", sans-serif; background: url('/a.png'); }</style></head><body></body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_escape_style_binding() {
+    let input = r#"
+    <div>
+        <p :style="{ color, fontSize: 12 + 'px', '--gap': '4px', margin: null }">a</p>
+        <p :style="[{ color: 'blue' }, 'font-weight: bold;']">b</p>
+        <p :style="'color: green'">c</p>
+    </div>
+    "#;
+    let output = render(input.to_string(), data()).unwrap();
+
+    let expected = r#"<html><head></head><body><div>
+        <p style="color:red\3b  \7d  body \7b  display: none;font-size:12px;--gap:4px;">a</p>
+        <p style="color:blue;font-weight: bold;">b</p>
+        <p style="color: green">c</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
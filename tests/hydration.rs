@@ -0,0 +1,144 @@
+use prevue::{Component, Options, Whitespace};
+use serde_json::{Value, json};
+
+fn data() -> Value {
+    json!({
+        "name": "Ada",
+        "list": [1, 2],
+    })
+}
+
+fn render(input: &str) -> String {
+    Options::new()
+        .hydration(true)
+        .render(input.to_string(), data())
+        .unwrap()
+}
+
+// === Lists ===
+
+#[test]
+fn test_hydration_for() {
+    let output = render(r#"<ul><li v-for="item in list">{{ item }}</li></ul>"#);
+
+    let expected =
+        "<html><head></head><body><ul><!--[--><li>1</li><li>2</li><!--]--></ul></body></html>";
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_hydration_for_empty() {
+    let output = render(r#"<ul><li v-for="item in []">{{ item }}</li></ul>"#);
+
+    let expected = "<html><head></head><body><ul><!--[--><!--]--></ul></body></html>";
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_hydration_for_template() {
+    // Each item is a fragment unless its template holds a single element
+    let output = render(
+        r#"<dl><template v-for="item in list"><dt>{{ item }}</dt><dd>-</dd></template></dl>"#,
+    );
+    let expected = "<html><head></head><body><dl><!--[--><!--[--><dt>1</dt><dd>-</dd><!--]--><!--[--><dt>2</dt><dd>-</dd><!--]--><!--]--></dl></body></html>";
+    assert_eq!(output, expected);
+
+    let output =
+        render(r#"<ul><template v-for="item in list"><li>{{ item }}</li></template></ul>"#);
+    let expected =
+        "<html><head></head><body><ul><!--[--><li>1</li><li>2</li><!--]--></ul></body></html>";
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_hydration_text_boundaries() {
+    // Text from a template is a fragment, so it stays a node of its own on the client
+    let output = render(
+        r#"<p>Hi <template v-if="name">{{ name }}</template>! <template v-for="item in list">{{ item }}</template></p>"#,
+    );
+
+    let expected = "<html><head></head><body><p>Hi <!--[-->Ada<!--]-->! <!--[--><!--[-->1<!--]--><!--[-->2<!--]--><!--]--></p></body></html>";
+    assert_eq!(output, expected);
+}
+
+// === Conditionals ===
+
+#[test]
+fn test_hydration_if() {
+    assert_eq!(
+        render(r#"<div><p v-if="list.length > 5">Many</p></div>"#),
+        "<html><head></head><body><div><!--v-if--></div></body></html>"
+    );
+    assert_eq!(
+        render(r#"<div><p v-if="list.length > 5">Many</p><p v-else>Few</p></div>"#),
+        "<html><head></head><body><div><p>Few</p></div></body></html>"
+    );
+    assert_eq!(
+        render(r#"<div><p v-if="list.length">Some</p><p v-else>None</p></div>"#),
+        "<html><head></head><body><div><p>Some</p></div></body></html>"
+    );
+}
+
+#[test]
+fn test_hydration_if_chain() {
+    // A chain that renders nothing leaves a single placeholder
+    let input = r#"<div><p v-if="list.length > 5">Many</p> <!-- note --> <p v-else-if="list.length > 3">Some</p></div>"#;
+    let output = render(input);
+
+    let expected = "<html><head></head><body><div> <!-- note --> <!--v-if--></div></body></html>";
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_hydration_if_template() {
+    let output = render(r#"<div><template v-if="name"><b>{{ name }}</b><i>!</i></template></div>"#);
+
+    let expected =
+        "<html><head></head><body><div><!--[--><b>Ada</b><i>!</i><!--]--></div></body></html>";
+    assert_eq!(output, expected);
+}
+
+// === Components ===
+
+#[test]
+fn test_hydration_component() {
+    // Components with several roots and slot contents are fragments
+    let pair = r#"<template><h2>{{ title }}</h2><slot>None</slot></template>"#;
+    let single = r#"<template><p><slot></slot></p></template>"#;
+    let options = Options::new()
+        .hydration(true)
+        .component("pair", Component::parse(pair).unwrap())
+        .component("single", Component::parse(single).unwrap());
+
+    let output = options
+        .render(
+            r#"<div><component is="pair" title="T"><b>{{ name }}</b></component><component is="single"></component></div>"#.to_string(),
+            data(),
+        )
+        .unwrap();
+
+    let expected = "<html><head></head><body><div><!--[--><h2>T</h2><!--[--><b>Ada</b><!--]--><!--]--><p><!--[--><!--]--></p></div></body></html>";
+    assert_eq!(output, expected);
+}
+
+// === Formatting ===
+
+#[test]
+fn test_hydration_minify() {
+    // Minifying removes comments but keeps the markers
+    let input = r#"
+    <div>
+        <!-- list -->
+        <span v-for="item in list">{{ item }}</span>
+        <p v-if="!name">Anonymous</p>
+    </div>
+    "#;
+    let output = Options::new()
+        .hydration(true)
+        .whitespace(Whitespace::Minify)
+        .render(input.to_string(), data())
+        .unwrap();
+
+    let expected = "<html><head></head><body><div><!--[--><span>1</span><span>2</span><!--]--><!--v-if--></div></body></html>";
+    assert_eq!(output, expected);
+}
@@ -0,0 +1,128 @@
+use prevue::Options;
+use serde_json::{Value, json};
+
+fn data() -> Value {
+    json!({
+        "name": "Alice",
+        "count": 3,
+    })
+}
+
+fn options() -> Options {
+    Options::new()
+        .messages(
+            "en",
+            json!({
+                "greeting": "Hello, {name}!",
+                "list": "{0} and {1}",
+                "nav": { "home": "Home", "about": "About us" },
+                "flat.key": "Flat",
+                "apples": "no apples | one apple | {count} apples",
+                "cars": "car | cars",
+                "only_en": "English only",
+            }),
+        )
+        .messages(
+            "fr",
+            json!({
+                "greeting": "Bonjour, {name} !",
+                "nav": { "home": "Accueil" },
+            }),
+        )
+        .messages(
+            "pl",
+            json!({
+                "files": { "one": "{n} plik", "few": "{n} pliki", "many": "{n} plików", "other": "{n} pliku" },
+            }),
+        )
+        .fallback_locale("en")
+}
+
+fn render(input: &str, options: Options) -> String {
+    options.render(input.to_string(), data()).unwrap()
+}
+
+// === $t ===
+
+#[test]
+fn test_i18n_t() {
+    let input = r#"
+    <div>
+        <p>{{ $t("greeting", { name }) }}</p>
+        <p>{{ $t("list", ["a", "b"]) }}</p>
+        <p>{{ $t("nav.home") }} {{ $t("nav.about") }}</p>
+        <p>{{ $t("flat.key") }}</p>
+        <p :title="$t('nav.home')">{{ $t("missing.key") }}</p>
+    </div>
+    "#;
+    let output = render(input, options());
+
+    let expected = r#"<html><head></head><body><div>
+        <p>Hello, Alice!</p>
+        <p>a and b</p>
+        <p>Home About us</p>
+        <p>Flat</p>
+        <p title="Home">missing.key</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_i18n_locale_per_render() {
+    let input = r#"<p>{{ $t("greeting", { name }) }} {{ $t("nav.home") }} {{ $t("nav.about") }} {{ $t("only_en") }}</p>"#;
+    let options = options();
+
+    let french = render(input, options.clone().locale("fr-CA"));
+    let english = render(input, options.locale("en-US"));
+
+    assert_eq!(
+        french,
+        "<html><head></head><body><p>Bonjour, Alice ! Accueil About us English only</p></body></html>"
+    );
+    assert_eq!(
+        english,
+        "<html><head></head><body><p>Hello, Alice! Home About us English only</p></body></html>"
+    );
+}
+
+// === $tc ===
+
+#[test]
+fn test_i18n_tc_choices() {
+    let input = r#"
+    <div>
+        <p v-for="n in [0, 1, count]">{{ $tc("apples", n) }}</p>
+        <p>{{ $tc("cars", 1) }} {{ $tc("cars", 2) }}</p>
+    </div>
+    "#;
+    let output = render(input, options());
+
+    let expected = r#"<html><head></head><body><div>
+        <p>no apples</p>
+        <p>one apple</p>
+        <p>3 apples</p>
+        <p>car cars</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_i18n_tc_plural_categories() {
+    let input = r#"<p v-for="n in [1, 3, 5]">{{ $tc("files", n) }}</p>"#;
+    let output = render(input, options().locale("pl"));
+
+    let expected =
+        "<html><head></head><body><p>1 plik</p><p>3 pliki</p><p>5 plików</p></body></html>";
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_i18n_sandbox() {
+    let input = r#"<p>{{ $t("greeting", { name }) }}</p>"#;
+    let output = render(input, options().locale("fr").sandbox(true));
+
+    let expected = "<html><head></head><body><p>Bonjour, Alice !</p></body></html>";
+    assert_eq!(output, expected);
+}
@@ -138,16 +138,58 @@ fn test_if_with_else_if_on_same_element() {
 
 #[test]
 fn test_if_takes_priority_over_for() {
-    // v-if takes precedence over v-for — element renders once, not per iteration
+    // Like Vue 3, v-if is checked first and the element is then repeated by v-for
     let input = r#"
     <div>
-        <div v-if="true" v-for="item in list">IF</div>
+        <div v-if="true" v-for="item in list">IF{{ item }}</div>
+        <p v-if="false" v-for="item in list">{{ item }}</p>
     </div>
     "#;
     let output = render(input.to_string(), data()).unwrap();
 
     let expected = r#"<html><head></head><body><div>
-        <div>IF</div>
+        <div>IF1</div>
+        <div>IF2</div>
+        <div>IF3</div>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_if_chain_with_for() {
+    let input = r#"
+    <div>
+        <p v-if="list.length > 5">many</p>
+        <p v-else v-for="item in list">{{ item }}</p>
+    </div>
+    "#;
+    let output = render(input.to_string(), data()).unwrap();
+
+    let expected = r#"<html><head></head><body><div>
+        <p>1</p>
+        <p>2</p>
+        <p>3</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_if_with_template_for() {
+    let input = r#"
+    <div>
+        <template v-if="user.age > 20" v-for="item in list" :key="item">
+            <b>{{ item }}</b>
+        </template>
+    </div>
+    "#;
+    let output = render(input.to_string(), data()).unwrap();
+
+    let expected = r#"<html><head></head><body><div>
+        <b>1</b>
+        <b>2</b>
+        <b>3</b>
     </div>
     </body></html>"#;
     assert_eq!(output, expected);
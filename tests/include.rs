@@ -0,0 +1,178 @@
+use prevue::{Loader, Options, TemplateError};
+use serde_json::{Value, json};
+
+fn data() -> Value {
+    json!({
+        "title": "Posts",
+        "posts": [
+            { "title": "First", "tags": ["a", "b"] },
+            { "title": "Second", "tags": [] },
+        ],
+    })
+}
+
+fn loader() -> Loader {
+    Loader::new()
+        .template("post.html", "<li>{{ post.title }} ({{ count }})</li>")
+        .template(
+            "header.html",
+            "<h1>{{ title }}</h1>\n<include src=\"tags.html\" :tags=\"[1, 2]\"></include>",
+        )
+        .template("tags.html", "<p>{{ tags.join(\", \") }}</p>")
+        .template("card.html", "<p>{{ heading }} {{ typeof title }}</p>")
+        .template(
+            "a.html",
+            "<div>\n  <include src=\"b.html\"></include>\n</div>",
+        )
+        .template("b.html", "<p>\n  <include src=\"a.html\"></include>\n</p>")
+        .template("broken.html", "<p>\n  {{ unclosed }\n</p>")
+}
+
+fn render(input: &str) -> String {
+    Options::new()
+        .loader(loader())
+        .render(input.to_string(), data())
+        .unwrap()
+}
+
+fn render_error(input: &str) -> TemplateError {
+    Options::new()
+        .loader(loader())
+        .render(input.to_string(), data())
+        .unwrap_err()
+        .downcast::<TemplateError>()
+        .unwrap()
+}
+
+// === Scope ===
+
+#[test]
+fn test_include_scope() {
+    // Includes see the current scope, including v-for aliases, and their attributes
+    let input = r#"
+    <ul>
+        <include v-for="post in posts" src="post.html" :count="post.tags.length"></include>
+    </ul>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><ul>
+        <li>First (2)</li>
+        <li>Second (0)</li>
+    </ul>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_include_only() {
+    // With `only`, the included template sees its attributes alone
+    let input = r#"<div><include src="card.html" :heading="title" only></include></div>"#;
+    let output = render(input);
+
+    let expected = "<html><head></head><body><div><p>Posts undefined</p></div></body></html>";
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_include_nested() {
+    let input = r#"<header><include :src="'header.html'"></include></header>"#;
+    let output = render(input);
+
+    let expected =
+        "<html><head></head><body><header><h1>Posts</h1>\n<p>1, 2</p></header></body></html>";
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_include_self_closing() {
+    // `<include />` swallows its siblings like any unknown element; they are kept after it
+    let input = r#"<div><include src="card.html" heading="Hi" /><span>{{ title }}</span></div>"#;
+    let output = render(input);
+
+    let expected =
+        "<html><head></head><body><div><p>Hi string</p><span>Posts</span></div></body></html>";
+    assert_eq!(output, expected);
+}
+
+// === Loader ===
+
+#[test]
+fn test_include_dir() {
+    let dir = std::env::temp_dir().join(format!("prevue-include-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("partials")).unwrap();
+    std::fs::write(dir.join("partials/title.html"), "<h1>{{ title }}</h1>").unwrap();
+    std::fs::write(
+        dir.join("page.html"),
+        "<main>\n<include src=\"partials/title.html\"></include>\n</main>",
+    )
+    .unwrap();
+
+    let options = Options::new().loader(Loader::new().dir(&dir));
+    let output = options.render_template("page.html", data()).unwrap();
+    let escape = options
+        .render(
+            r#"<include src="../etc/passwd"></include>"#.to_string(),
+            data(),
+        )
+        .unwrap_err();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        output,
+        "<html><head></head><body><main>\n<h1>Posts</h1>\n</main></body></html>"
+    );
+    assert_eq!(
+        escape.downcast_ref::<TemplateError>().unwrap().message,
+        "template name `../etc/passwd` must be a relative path inside the template directories"
+    );
+}
+
+// === Errors ===
+
+#[test]
+fn test_include_errors() {
+    // Errors report the template they are in
+    assert_eq!(
+        render_error(r#"<div><include src="broken.html"></include></div>"#),
+        TemplateError {
+            file: Some("broken.html".to_string()),
+            line: Some(2),
+            message: "unterminated interpolation `{{ unclosed }`".to_string(),
+        }
+    );
+    assert_eq!(
+        render_error("<div>\n<include src=\"missing.html\"></include>\n</div>"),
+        TemplateError {
+            file: None,
+            line: Some(2),
+            message: "template `missing.html` not found".to_string(),
+        }
+    );
+    assert_eq!(
+        render_error("<include></include>").message,
+        "`<include>` has no `src`"
+    );
+}
+
+#[test]
+fn test_include_cycle() {
+    assert_eq!(
+        render_error(r#"<include src="a.html"></include>"#),
+        TemplateError {
+            file: Some("b.html".to_string()),
+            line: Some(2),
+            message: "include cycle: a.html -> b.html -> a.html".to_string(),
+        }
+    );
+
+    // A template rendered by name is part of the cycle
+    let error = Options::new()
+        .loader(loader())
+        .render_template("b.html", data())
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "include cycle: b.html -> a.html -> b.html at a.html:2"
+    );
+}
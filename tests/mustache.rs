@@ -1,4 +1,4 @@
-use prevue::render;
+use prevue::{TemplateError, render};
 use serde_json::{Value, json};
 
 fn data() -> Value {
@@ -66,34 +66,105 @@ fn test_mustache_multiline() {
 
 #[test]
 fn test_mustache_unclosed() {
-    // Unclosed mustache should be left as is or ignored. Let's see what prevue currently outputs.
-    // Actually, in test_for_array in tests/for.rs: <h1>{{ notclosed }</h1> outputs as <h1>{{ notclosed }</h1>
     let input = r#"
     <div>
         {{ unclosed }
     </div>
     "#;
+    let error = render(input.to_string(), data()).unwrap_err();
+
+    let error = error.downcast_ref::<TemplateError>().unwrap();
+    assert_eq!(error.line, Some(3));
+    assert_eq!(error.message, "unterminated interpolation `{{ unclosed }`");
+}
+
+#[test]
+fn test_mustache_unclosed_in_for() {
+    let input = r#"
+    <ul>
+        <li v-for="item in list">
+            {{ item }
+        </li>
+    </ul>
+    "#;
+    let error = render(input.to_string(), data()).unwrap_err();
+
+    let error = error.downcast_ref::<TemplateError>().unwrap();
+    assert_eq!(error.line, Some(4));
+}
+
+#[test]
+fn test_mustache_empty() {
+    // Empty mustache evaluates to empty or undefined
+    let input = r#"
+    <div>
+        [{{ }}]
+    </div>
+    "#;
     let output = render(input.to_string(), data()).unwrap();
 
     let expected = r#"<html><head></head><body><div>
-        {{ unclosed }
+        []
     </div>
     </body></html>"#;
     assert_eq!(output, expected);
 }
 
+// === Nesting ===
+
 #[test]
-fn test_mustache_empty() {
-    // Empty mustache evaluates to empty or undefined
+fn test_mustache_braces() {
     let input = r#"
     <div>
-        [{{ }}]
+        <p>{{ { a: 1 }.a }}</p>
+        <p>{{ JSON.stringify({ x: { y: 1 } }) }}</p>
+        <p>{{ ((v) => ({ v }))(user.name).v }}</p>
     </div>
     "#;
     let output = render(input.to_string(), data()).unwrap();
 
     let expected = r#"<html><head></head><body><div>
-        []
+        <p>1</p>
+        <p>{"x":{"y":1}}</p>
+        <p>Alice</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_mustache_string_literal() {
+    let input = r#"
+    <div>
+        <p>{{ "}}" }}</p>
+        <p>{{ '{{ x }}' }}</p>
+        <p>{{ "a\"}}" }}</p>
+    </div>
+    "#;
+    let output = render(input.to_string(), data()).unwrap();
+
+    let expected = r#"<html><head></head><body><div>
+        <p>}}</p>
+        <p>{{ x }}</p>
+        <p>a"}}</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_mustache_template_literal() {
+    let input = r#"
+    <div>
+        <p>{{ `}}${ user.name }}}` }}</p>
+        <p>{{ `${ `${ { a: list.length }.a }` }!` }}</p>
+    </div>
+    "#;
+    let output = render(input.to_string(), data()).unwrap();
+
+    let expected = r#"<html><head></head><body><div>
+        <p>}}Alice}}</p>
+        <p>3!</p>
     </div>
     </body></html>"#;
     assert_eq!(output, expected);
@@ -207,7 +278,7 @@ fn test_mustache_error() {
 
 #[test]
 fn test_mustache_this() {
-    // can't serialize this
+    // `this` is the data
     let input = r#"
     <div>
         {{ this }}
@@ -216,7 +287,7 @@ fn test_mustache_this() {
     let output = render(input.to_string(), data()).unwrap();
 
     let expected = r#"<html><head></head><body><div>
-        
+        { "list": [ 1, 2, 3 ], "user": { "name": "Alice", "age": 21 } }
     </div>
     </body></html>"#;
     assert_eq!(output, expected);
@@ -248,7 +319,6 @@ fn test_mustache_comment() {
 
 #[test]
 fn test_mustache_this_json() {
-    // can't serialize this
     let input = r#"
     <div>
         {{ JSON.stringify(this) }}
@@ -257,7 +327,7 @@ fn test_mustache_this_json() {
     let output = render(input.to_string(), data()).unwrap();
 
     let expected = r#"<html><head></head><body><div>
-        {"__scope_0":{"list":[1,2,3],"user":{"name":"Alice","age":21}}}
+        {"list":[1,2,3],"user":{"name":"Alice","age":21}}
     </div>
     </body></html>"#;
     assert_eq!(output, expected);
@@ -0,0 +1,93 @@
+use prevue::{Component, InitialState, Options};
+use serde_json::{Value, json};
+
+fn data() -> Value {
+    json!({ "show": true })
+}
+
+fn render(input: &str) -> String {
+    Options::new()
+        .nonce("n0nce")
+        .render(input.to_string(), data())
+        .unwrap()
+}
+
+// === Elements ===
+
+#[test]
+fn test_nonce_elements() {
+    let input = r#"
+    <style>p { color: red; }</style>
+    <script src="/app.js"></script>
+    <div>
+        <script v-if="show">init()</script>
+        <style nonce="kept"></style>
+        <template v-for="i in 1"><script>run(i)</script></template>
+    </div>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head><style nonce="n0nce">p { color: red; }</style>
+    <script src="/app.js" nonce="n0nce"></script>
+    </head><body><div>
+        <script nonce="n0nce">init()</script>
+        <style nonce="kept"></style>
+        <script nonce="n0nce">run(i)</script>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_nonce_v_pre() {
+    let input = r#"<div v-pre><script>{{ raw }}</script></div>"#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><div><script nonce="n0nce">{{ raw }}</script></div></body></html>"#;
+    assert_eq!(output, expected);
+}
+
+// === Variable ===
+
+#[test]
+fn test_nonce_variable() {
+    let input = r#"<link rel="modulepreload" href="/app.js" :nonce="$nonce"><p>{{ $nonce }}</p>"#;
+    let output = render(input);
+
+    let expected = r#"<html><head><link rel="modulepreload" href="/app.js" nonce="n0nce"></head><body><p>n0nce</p></body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_nonce_sandbox() {
+    let input = r#"<p>{{ $nonce }}</p>"#;
+    let output = Options::new()
+        .nonce("n0nce")
+        .sandbox(true)
+        .render(input.to_string(), data())
+        .unwrap();
+
+    assert_eq!(
+        output,
+        "<html><head></head><body><p>n0nce</p></body></html>"
+    );
+}
+
+// === Injected ===
+
+#[test]
+fn test_nonce_injected() {
+    // Injected styles and the initial state script get the nonce too
+    let source = "<template><p>Hi</p></template><style>p { margin: 0; }</style>";
+    let component = Component::parse(source).unwrap();
+    let output = Options::new()
+        .nonce("n0nce")
+        .inject_styles(true)
+        .initial_state(InitialState::new())
+        .render_component(&component, json!({}))
+        .unwrap();
+
+    let expected = r#"<html><head><style nonce="n0nce">p { margin: 0; }
+</style></head><body><p>Hi</p><script nonce="n0nce">window.__INITIAL_STATE__={};</script></body></html>"#;
+    assert_eq!(output, expected);
+}
@@ -0,0 +1,169 @@
+use prevue::Options;
+use serde_json::{Value, json};
+
+fn data() -> Value {
+    json!({
+        "stamp": 1_700_000_000_000_i64,
+        "title": "Héllo, Wörld!",
+    })
+}
+
+fn render(input: &str) -> String {
+    Options::new()
+        .prelude(true)
+        .locale("en-US")
+        .render(input.to_string(), data())
+        .unwrap()
+}
+
+// === Dates ===
+
+#[test]
+fn test_prelude_format_date() {
+    let input = r#"
+    <div>
+        <p>{{ formatDate(0, "%Y-%m-%d %H:%M:%S", { utc: true }) }}</p>
+        <p>{{ formatDate(stamp, "%a, %e %b %Y %I:%M %p", { utc: true }) }}</p>
+        <p>{{ formatDate(stamp, "iso") }}</p>
+        <p>{{ formatDate("not a date") }}</p>
+    </div>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><div>
+        <p>1970-01-01 00:00:00</p>
+        <p>Tue, 14 Nov 2023 10:13 PM</p>
+        <p>2023-11-14T22:13:20.000Z</p>
+        <p></p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_prelude_time_ago() {
+    let input = r#"
+    <div>
+        <p>{{ timeAgo(stamp - 3 * 86400000, stamp) }}</p>
+        <p>{{ timeAgo(stamp + 2 * 3600000, stamp) }}</p>
+        <p>{{ timeAgo(stamp - 60000, stamp) }}</p>
+        <p>{{ timeAgo(stamp, stamp) }}</p>
+    </div>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><div>
+        <p>3 days ago</p>
+        <p>in 2 hours</p>
+        <p>1 minute ago</p>
+        <p>just now</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+// === Numbers ===
+
+#[test]
+fn test_prelude_format_number() {
+    let input = r#"
+    <div>
+        <p>{{ formatNumber(1234567.891, 2) }}</p>
+        <p>{{ formatCurrency(1234.5, "EUR") }}</p>
+        <p>{{ formatCurrency(3) }}</p>
+        <p>{{ formatCurrency(-1200, "JPY") }} {{ formatCurrency(2, "CHF") }}</p>
+    </div>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><div>
+        <p>1,234,567.89</p>
+        <p>€1,234.50</p>
+        <p>$3.00</p>
+        <p>-¥1,200 CHF 2.00</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_prelude_format_bytes() {
+    let input = r#"
+    <div>
+        <p>{{ formatBytes(512) }}</p>
+        <p>{{ formatBytes(1536) }}</p>
+        <p>{{ formatBytes(1048576) }}</p>
+        <p>{{ formatBytes(1536, 0) }}</p>
+    </div>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><div>
+        <p>512 B</p>
+        <p>1.5 KB</p>
+        <p>1 MB</p>
+        <p>2 KB</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+// === Text ===
+
+#[test]
+fn test_prelude_text() {
+    let input = r#"
+    <div>
+        <p>{{ truncate("Hello wonderful world", 10) }}</p>
+        <p>{{ truncate("short", 10) }}</p>
+        <p>{{ slugify(title) }}</p>
+        <p>{{ pluralize(1, "item") }} {{ pluralize(2, "item") }} {{ pluralize(0, "child", "children") }}</p>
+    </div>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><div>
+        <p>Hello won…</p>
+        <p>short</p>
+        <p>hello-world</p>
+        <p>item items children</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+// === Scope ===
+
+#[test]
+fn test_prelude_shadowed_by_data() {
+    let input = r#"<p>{{ truncate }}</p>"#;
+    let output = Options::new()
+        .prelude(true)
+        .render(input.to_string(), json!({ "truncate": "data" }))
+        .unwrap();
+
+    let expected = "<html><head></head><body><p>data</p></body></html>";
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_prelude_sandbox() {
+    let input = r#"<a :href="'/posts/' + slugify(title)">{{ formatDate(stamp, "iso") }}</a>"#;
+    let output = Options::new()
+        .prelude(true)
+        .sandbox(true)
+        .render(input.to_string(), data())
+        .unwrap();
+
+    let expected = r#"<html><head></head><body><a href="/posts/hello-world">2023-11-14T22:13:20.000Z</a></body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_prelude_disabled() {
+    let input = r#"<p>{{ typeof slugify }}</p>"#;
+    let output = Options::new().render(input.to_string(), data()).unwrap();
+
+    let expected = "<html><head></head><body><p>undefined</p></body></html>";
+    assert_eq!(output, expected);
+}
@@ -0,0 +1,111 @@
+use prevue::{Renderer, render};
+use serde_json::json;
+use std::thread;
+
+#[test]
+fn test_renderer_matches_render() {
+    let input = r#"
+    <div>
+        <p v-if="show">{{ message }}</p>
+        <li v-for="item in list">{{ item }}</li>
+    </div>
+    "#;
+    let data = json!({ "show": true, "message": "Hello", "list": [1, 2] });
+
+    let renderer = Renderer::new(2);
+    let output = renderer.render(input, &data).unwrap();
+
+    assert_eq!(output, render(input.to_string(), &data).unwrap());
+}
+
+#[test]
+fn test_renderer_reuses_template() {
+    // The same template rendered twice must not leak state between renders
+    let renderer = Renderer::new(1);
+    let input = r#"<p v-for="item in list">{{ item }}</p>"#;
+
+    let first = renderer.render(input, json!({ "list": [1, 2] })).unwrap();
+    let second = renderer.render(input, json!({ "list": [3] })).unwrap();
+
+    assert_eq!(
+        first,
+        "<html><head></head><body><p>1</p><p>2</p></body></html>"
+    );
+    assert_eq!(second, "<html><head></head><body><p>3</p></body></html>");
+}
+
+#[test]
+fn test_renderer_evicts_templates() {
+    // More templates than a worker caches, with the first one kept in use
+    let renderer = Renderer::new(1);
+    let first = "<p>{{ n }}</p>";
+
+    for i in 0..100 {
+        let input = format!("<p id=\"t{i}\">{{{{ n }}}}</p>");
+        let output = renderer.render(input, json!({ "n": i })).unwrap();
+        assert_eq!(
+            output,
+            format!("<html><head></head><body><p id=\"t{i}\">{i}</p></body></html>")
+        );
+
+        let output = renderer.render(first, json!({ "n": i })).unwrap();
+        assert_eq!(
+            output,
+            format!("<html><head></head><body><p>{i}</p></body></html>")
+        );
+    }
+}
+
+#[test]
+fn test_renderer_shared_across_threads() {
+    let renderer = Renderer::new(4);
+
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let renderer = renderer.clone();
+            thread::spawn(move || renderer.render("<p>{{ n }}</p>", json!({ "n": i })))
+        })
+        .collect();
+
+    for (i, handle) in handles.into_iter().enumerate() {
+        let output = handle.join().unwrap().unwrap();
+        assert_eq!(
+            output,
+            format!("<html><head></head><body><p>{i}</p></body></html>")
+        );
+    }
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_renderer_async() {
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    struct Unpark(thread::Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+            thread::park();
+        }
+    }
+
+    let renderer = Renderer::new(1);
+    let output = block_on(renderer.render_async("<p>{{ n }}</p>", json!({ "n": 1 }))).unwrap();
+
+    assert_eq!(output, "<html><head></head><body><p>1</p></body></html>");
+}
@@ -0,0 +1,146 @@
+use prevue::Options;
+use serde_json::{Value, json};
+
+fn data() -> Value {
+    json!({
+        "title": "Sandbox",
+        "list": [
+            { "name": "a", "count": 1 },
+            { "name": "b", "count": 2 },
+        ],
+    })
+}
+
+fn render(input: &str) -> String {
+    Options::new()
+        .sandbox(true)
+        .render(input.to_string(), data())
+        .unwrap()
+}
+
+// === Data ===
+
+#[test]
+fn test_sandbox_data() {
+    let input = r#"
+    <ul :title="title">
+        <li v-for="({ name, count }, index) in list" :data-count="count">{{ index }}: {{ name }}</li>
+        <li>{{ list.map((item) => item.name).join(", ") }}</li>
+        <li>{{ this.title }}</li>
+    </ul>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><ul title="Sandbox">
+        <li data-count="1">0: a</li>
+        <li data-count="2">1: b</li>
+        <li>a, b</li>
+        <li>Sandbox</li>
+    </ul>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_sandbox_statements() {
+    // Bindings are single expressions
+    let input = r#"<p>{{ let x = 1; x }}</p>"#;
+    let output = render(input);
+
+    let expected = "<html><head></head><body><p></p></body></html>";
+    assert_eq!(output, expected);
+}
+
+// === Globals ===
+
+#[test]
+fn test_sandbox_globals() {
+    let input = r#"
+    <div>
+        <p>{{ Math.max(1, 2) }} {{ JSON.stringify([1]) }} {{ Number("3") }} {{ Object.keys(list[0]) }}</p>
+        <p>{{ typeof eval }} {{ typeof Function }} {{ typeof globalThis }} {{ typeof __scope_0 }}</p>
+        <p>{{ typeof this.eval }} {{ (function () { return typeof this.eval })() }}</p>
+        <p>{{ typeof missing }}</p>
+    </div>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><div>
+        <p>2 [1] 3 [ "name", "count" ]</p>
+        <p>undefined undefined undefined undefined</p>
+        <p>undefined undefined</p>
+        <p>undefined</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_sandbox_function_constructor() {
+    let input = r#"
+    <div>
+        <p>{{ (() => 1).constructor("return 1")() }}</p>
+        <p>{{ [].map.constructor("return 1")() }}</p>
+        <p>{{ Object.getPrototypeOf(async () => {}).constructor("return 1") }}</p>
+        <p>{{ Object.getPrototypeOf(function* () {}).constructor("yield 1") }}</p>
+        <p>{{ (() => { try { (() => 1).constructor("return 1") } catch (e) { return `${e.name}: ${e.message}` } })() }}</p>
+    </div>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><div>
+        <p></p>
+        <p></p>
+        <p></p>
+        <p></p>
+        <p>TypeError: code generation is disabled</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_sandbox_frozen_intrinsics() {
+    let input = r#"
+    <div>
+        <p>{{ (Object.prototype.polluted = 1, ({}).polluted) }}</p>
+        <p>{{ (Math.max = () => 0, Math.max(1, 2)) }}</p>
+        <p>{{ typeof [].polluted }}</p>
+    </div>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><div>
+        <p></p>
+        <p>2</p>
+        <p>undefined</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
+
+// === Scopes ===
+
+#[test]
+fn test_sandbox_scope_isolation() {
+    // Assignments neither leak into later expressions nor change shared data
+    let input = r#"
+    <div>
+        <p>{{ (leaked = 1, typeof leaked) }}</p>
+        <p>{{ typeof leaked }}</p>
+        <p v-for="item in list">{{ (item.name = "x", title = "x", item.name + title) }}</p>
+        <p>{{ list[0].name }} {{ title }}</p>
+    </div>
+    "#;
+    let output = render(input);
+
+    let expected = r#"<html><head></head><body><div>
+        <p>undefined</p>
+        <p>undefined</p>
+        <p>aSandbox</p>
+        <p>bSandbox</p>
+        <p>a Sandbox</p>
+    </div>
+    </body></html>"#;
+    assert_eq!(output, expected);
+}
@@ -0,0 +1,207 @@
+use prevue::{Build, Site};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Site directory and its output, removed when dropped
+struct Dir(PathBuf);
+
+impl Dir {
+    fn new(name: &str, files: &[(&str, &str)]) -> Self {
+        let dir = std::env::temp_dir().join(format!("prevue-site-{name}-{}", std::process::id()));
+        for (file, contents) in files {
+            write(&dir.join("site").join(file), contents);
+        }
+        Self(dir)
+    }
+
+    fn site(&self) -> Site {
+        Site::new(self.0.join("site"), self.0.join("out")).layout("base.html")
+    }
+
+    fn write(&self, file: &str, contents: &str) {
+        write(&self.0.join("site").join(file), contents);
+    }
+
+    fn output(&self, file: &str) -> String {
+        fs::read_to_string(self.0.join("out").join(file)).unwrap()
+    }
+}
+
+impl Drop for Dir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn write(path: &Path, contents: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+fn paths(paths: &[&str]) -> Vec<PathBuf> {
+    paths.iter().map(PathBuf::from).collect()
+}
+
+const POSTS: &str = r#"[{ "slug": "hello", "title": "Hello" }, { "slug": "bye", "title": "Bye" }]"#;
+
+fn site(name: &str) -> Dir {
+    Dir::new(
+        name,
+        &[
+            (
+                "layouts/base.html",
+                r#"<html><head><title>{{ title }}</title></head><body><include :src="$page"></include></body></html>"#,
+            ),
+            ("partials/nav.html", "<nav>{{ $path }}</nav>"),
+            (
+                "components/Badge.vue",
+                "<template><b>{{ label }}</b></template>",
+            ),
+            ("data/posts.json", POSTS),
+            (
+                "pages/index.html",
+                r#"<include src="partials/nav.html"></include><ul><li v-for="post in posts">{{ post.title }}</li></ul>"#,
+            ),
+            ("pages/index.json", r#"{ "title": "Home" }"#),
+            (
+                "pages/posts/[slug].html",
+                r#"<h1>{{ post.title }}</h1><component is="Badge" :label="post.slug"></component>"#,
+            ),
+            (
+                "pages/posts/[slug].json",
+                r#"{ "$for": "post in posts", "title": "Post" }"#,
+            ),
+            ("pages/raw.html", "<p>raw</p>"),
+            ("pages/raw.json", r#"{ "layout": false }"#),
+            ("public/style.css", "p { margin: 0; }"),
+        ],
+    )
+}
+
+// === Pages ===
+
+#[test]
+fn test_site_build() {
+    let dir = site("build");
+    let build = dir.site().build().unwrap();
+
+    assert_eq!(
+        build,
+        Build {
+            rendered: paths(&[
+                "index.html",
+                "posts/hello.html",
+                "posts/bye.html",
+                "raw.html"
+            ]),
+            copied: paths(&["style.css"]),
+            unchanged: Vec::new(),
+            removed: Vec::new(),
+        }
+    );
+    assert_eq!(
+        dir.output("index.html"),
+        "<html><head><title>Home</title></head><body><nav>index.html</nav><ul><li>Hello</li><li>Bye</li></ul></body></html>"
+    );
+    assert_eq!(
+        dir.output("posts/bye.html"),
+        "<html><head><title>Post</title></head><body><h1>Bye</h1><b>bye</b></body></html>"
+    );
+    assert_eq!(
+        dir.output("raw.html"),
+        "<html><head></head><body><p>raw</p></body></html>"
+    );
+    assert_eq!(dir.output("style.css"), "p { margin: 0; }");
+}
+
+#[test]
+fn test_site_errors() {
+    let dir = Dir::new("errors", &[("pages/[id].html", "<p>{{ id }}</p>")]);
+    let error = dir.site().build().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "`pages/[id].html` has a `[param]` in its name but no `$for` in its data"
+    );
+
+    dir.write(
+        "pages/[id].json",
+        r#"{ "$for": "id in ids", "ids": [1, "../x"] }"#,
+    );
+    let error = dir.site().build().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "`[id]` of `pages/[id].html` is `../x`, which is not a file name"
+    );
+}
+
+// === Incremental builds ===
+
+#[test]
+fn test_site_incremental() {
+    let dir = site("incremental");
+    dir.site().build().unwrap();
+
+    // Nothing changed
+    let build = dir.site().build().unwrap();
+    assert!(build.rendered.is_empty() && build.copied.is_empty());
+    assert_eq!(build.unchanged.len(), 5);
+
+    // A page changed
+    dir.write("pages/raw.html", "<p>changed</p>");
+    let build = dir.site().build().unwrap();
+    assert_eq!(build.rendered, paths(&["raw.html"]));
+    assert_eq!(
+        dir.output("raw.html"),
+        "<html><head></head><body><p>changed</p></body></html>"
+    );
+
+    // Shared data changed, and a generated page is gone
+    dir.write(
+        "data/posts.json",
+        r#"[{ "slug": "hello", "title": "Hello again" }]"#,
+    );
+    let build = dir.site().build().unwrap();
+    assert_eq!(
+        build.rendered,
+        paths(&["index.html", "posts/hello.html", "raw.html"])
+    );
+    assert_eq!(build.removed, paths(&["posts/bye.html"]));
+    assert_eq!(build.unchanged, paths(&["style.css"]));
+    assert!(!dir.0.join("out/posts/bye.html").exists());
+    assert_eq!(
+        dir.output("posts/hello.html"),
+        "<html><head><title>Post</title></head><body><h1>Hello again</h1><b>hello</b></body></html>"
+    );
+
+    // A deleted output is written again
+    fs::remove_file(dir.0.join("out/style.css")).unwrap();
+    let build = dir.site().build().unwrap();
+    assert_eq!(build.copied, paths(&["style.css"]));
+    assert!(build.rendered.is_empty());
+}
+
+#[test]
+fn test_site_tampered_manifest() {
+    // Paths of a manifest that lead outside the output directory are not removed
+    let dir = site("tampered");
+    dir.site().build().unwrap();
+
+    let outside = dir.0.join("outside.txt");
+    write(&outside, "keep");
+    let manifest = serde_json::json!({
+        "files": {
+            "../outside.txt": "0",
+            outside.to_string_lossy(): "0",
+            "posts/../../outside.txt": "0",
+            "": "0",
+        }
+    });
+    write(
+        &dir.0.join("out/.prevue-manifest.json"),
+        &manifest.to_string(),
+    );
+
+    let build = dir.site().build().unwrap();
+    assert!(build.removed.is_empty());
+    assert_eq!(fs::read_to_string(&outside).unwrap(), "keep");
+}
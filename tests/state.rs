@@ -0,0 +1,70 @@
+use prevue::{InitialState, Options, StateLocation};
+use serde_json::{Value, json};
+
+fn page() -> Value {
+    json!({
+        "title": "Hello",
+        "tags": ["a", "b"],
+    })
+}
+
+// === Locations ===
+
+#[test]
+fn test_state_body_end() {
+    let input = r#"<div id="app"><h1>{{ title }}</h1></div>"#;
+    let output = Options::new()
+        .initial_state(InitialState::new())
+        .render(input.to_string(), page())
+        .unwrap();
+
+    let expected = r#"<html><head></head><body><div id="app"><h1>Hello</h1></div><script>window.__INITIAL_STATE__={"title":"Hello","tags":["a","b"]};</script></body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_state_body_start() {
+    let input = r#"<div id="app">{{ tags.length }}</div>"#;
+    let output = Options::new()
+        .initial_state(InitialState::new().location(StateLocation::BodyStart))
+        .render(input.to_string(), page())
+        .unwrap();
+
+    let expected = r#"<html><head></head><body><script>window.__INITIAL_STATE__={"title":"Hello","tags":["a","b"]};</script><div id="app">2</div></body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_state_head() {
+    let input = r#"<html><head><title>{{ title }}</title></head><body></body></html>"#;
+    let output = Options::new()
+        .initial_state(
+            InitialState::new()
+                .location(StateLocation::Head)
+                .nonce("abc123"),
+        )
+        .render(input.to_string(), page())
+        .unwrap();
+
+    let expected = r#"<html><head><title>Hello</title><script nonce="abc123">window.__INITIAL_STATE__={"title":"Hello","tags":["a","b"]};</script></head><body></body></html>"#;
+    assert_eq!(output, expected);
+}
+
+// === Escaping ===
+
+#[test]
+fn test_state_escape() {
+    // Nothing in the data can end the script or open a comment
+    let data = json!({
+        "html": "</script><!--<script>",
+        "amp": "a && b",
+        "lines": "a\u{2028}b\u{2029}c",
+    });
+    let output = Options::new()
+        .initial_state(InitialState::new().name("my-state"))
+        .render("<p></p>".to_string(), data)
+        .unwrap();
+
+    let expected = r#"<html><head></head><body><p></p><script>window["my-state"]={"html":"\u003c/script\u003e\u003c!--\u003cscript\u003e","amp":"a \u0026\u0026 b","lines":"a\u2028b\u2029c"};</script></body></html>"#;
+    assert_eq!(output, expected);
+}
@@ -0,0 +1,186 @@
+use prevue::{InitialState, Options, StateLocation, render, render_stream};
+use serde_json::{Value, json};
+
+fn data() -> Value {
+    json!({
+        "title": "Report",
+        "list": [1, 2, 3],
+        "user": {
+            "name": "Alice",
+            "age": 21
+        },
+    })
+}
+
+fn chunks(input: &str) -> Vec<String> {
+    render_stream(input.to_string(), data())
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+#[test]
+fn test_stream_matches_render() {
+    let inputs = [
+        "",
+        r#"<p>{{ title }}</p>"#,
+        r#"
+    <!DOCTYPE html>
+    <html lang="en">
+    <head><title>{{ title }}</title></head>
+    <body :class="title">
+        <h1>{{ title }}</h1>
+        <section v-if="user.age < 18">minor</section>
+        <section v-else-if="user.age < 20">teen</section>
+        <section v-else>adult</section>
+        <ul>
+            <li v-for="item in list">{{ item }}</li>
+        </ul>
+        <p v-text="user.name"></p> tail
+        <template v-for="item in list">
+            <b>{{ item }}</b>
+            <i>{{ item }}</i>
+        </template>
+    </body>
+    </html>
+    "#,
+    ];
+
+    for input in inputs {
+        let expected = render(input.to_string(), data()).unwrap();
+        assert_eq!(chunks(input).concat(), expected);
+    }
+}
+
+#[test]
+fn test_stream_sections() {
+    let input = r#"
+    <section>{{ title }}</section>
+    <section v-for="item in list">{{ item }}</section>
+    <footer>end</footer>
+    "#;
+
+    assert_eq!(
+        chunks(input),
+        [
+            "<html><head></head><body>",
+            "<section>Report</section>",
+            "\n    <section>1</section>\n    <section>2</section>\n    <section>3</section>",
+            "\n    <footer>end</footer>",
+            "\n    ",
+            "</body></html>",
+        ]
+    );
+}
+
+#[test]
+fn test_stream_adjacent_sections() {
+    // Each section is emitted before the next one renders
+    let input = r#"<section>a</section><section>b</section><section>c</section>"#;
+
+    assert_eq!(
+        chunks(input),
+        [
+            "<html><head></head><body>",
+            "<section>a</section>",
+            "<section>b</section>",
+            "<section>c</section>",
+            "</body></html>",
+        ]
+    );
+}
+
+// === Options ===
+
+#[test]
+fn test_stream_initial_state() {
+    let input = r#"<section>{{ title }}</section><footer>end</footer>"#;
+    let stream = |location| {
+        Options::new()
+            .initial_state(InitialState::new().location(location))
+            .render_stream(input.to_string(), json!({ "title": "Report" }))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    };
+    let script = r#"<script>window.__INITIAL_STATE__={"title":"Report"};</script>"#;
+
+    assert_eq!(
+        stream(StateLocation::Head),
+        [
+            format!("<html><head>{script}</head><body>").as_str(),
+            "<section>Report</section>",
+            "<footer>end</footer>",
+            "</body></html>",
+        ]
+    );
+    assert_eq!(
+        stream(StateLocation::BodyStart),
+        [
+            "<html><head></head><body>",
+            format!("{script}<section>Report</section>").as_str(),
+            "<footer>end</footer>",
+            "</body></html>",
+        ]
+    );
+    assert_eq!(
+        stream(StateLocation::BodyEnd),
+        [
+            "<html><head></head><body>",
+            "<section>Report</section>",
+            format!("<footer>end</footer>{script}").as_str(),
+            "</body></html>",
+        ]
+    );
+}
+
+#[test]
+fn test_stream_fragment() {
+    let input = r#"<section>{{ title }}</section><p v-for="item in list">{{ item }}</p>"#;
+    let chunks = Options::new()
+        .fragment(true)
+        .render_stream(input.to_string(), data())
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        chunks,
+        ["<section>Report</section>", "<p>1</p><p>2</p><p>3</p>"]
+    );
+}
+
+#[test]
+fn test_stream_fragment_head_elements() {
+    // Leading elements the parser moves to `<head>` come first
+    let input = r#"<script>a()</script><template v-for="item in list"><b>{{ item }}</b></template><p>after</p>"#;
+    let chunks = Options::new()
+        .fragment(true)
+        .render_stream(input.to_string(), data())
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        chunks,
+        [
+            "<script>a()</script><b>1</b><b>2</b><b>3</b>",
+            "<p>after</p>"
+        ]
+    );
+}
+
+#[test]
+fn test_stream_inject_styles() {
+    // Styles are known once every component rendered, after `<head>` is sent
+    let error = Options::new()
+        .inject_styles(true)
+        .render_stream("<p></p>".to_string(), data())
+        .err()
+        .unwrap();
+
+    assert_eq!(
+        error.to_string(),
+        "`inject_styles` can't be used with `render_stream`, as `<head>` is sent before the components render"
+    );
+}
@@ -1,4 +1,4 @@
-use prevue::render;
+use prevue::{render, render_to_fmt, render_to_writer};
 use serde_json::{Value, json};
 
 fn data() -> Value {
@@ -103,3 +103,45 @@ fn test_attr_case() {
     </body></html>"#;
     assert_eq!(output, expected);
 }
+
+#[test]
+fn test_render_to_writer() {
+    let input = r#"<p v-for="item in list">{{ item }}</p>"#;
+
+    let mut output = Vec::new();
+    render_to_writer(input.to_string(), data(), &mut output).unwrap();
+
+    let expected = "<html><head></head><body><p>1</p><p>2</p><p>3</p></body></html>";
+    assert_eq!(String::from_utf8(output).unwrap(), expected);
+}
+
+#[test]
+fn test_render_to_fmt() {
+    let input = r#"<p>{{ user.name }} — 안녕</p>"#;
+
+    let mut output = String::new();
+    render_to_fmt(input.to_string(), data(), &mut output).unwrap();
+
+    let expected = "<html><head></head><body><p>Alice — 안녕</p></body></html>";
+    assert_eq!(output, expected);
+    assert_eq!(output, render(input.to_string(), data()).unwrap());
+}
+
+#[test]
+fn test_render_to_writer_error() {
+    struct Failing;
+
+    impl std::io::Write for Failing {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("sink closed"))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let result = render_to_writer("<p>hi</p>".to_string(), data(), &mut Failing);
+    let error = result.unwrap_err();
+    assert!(error.downcast_ref::<std::io::Error>().is_some());
+}
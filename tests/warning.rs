@@ -0,0 +1,223 @@
+use prevue::{Loader, Options, Warning, WarningKind, render, render_with_warnings};
+use serde_json::{Value, json};
+
+fn data() -> Value {
+    json!({
+        "list": [1, 2],
+        "user": { "name": "Alice" },
+    })
+}
+
+fn warnings(input: &str) -> Vec<(WarningKind, Option<usize>)> {
+    let (_, warnings) = render_with_warnings(input.to_string(), data()).unwrap();
+    warnings
+        .into_iter()
+        .map(|warning| (warning.kind, warning.line))
+        .collect()
+}
+
+// === Output ===
+
+#[test]
+fn test_warning_same_output() {
+    let input = r#"
+    <div>
+        <p v-else>orphan</p>
+        <p>{{ user.name }} {{ missing }}</p>
+    </div>
+    "#;
+    let (output, _) = render_with_warnings(input.to_string(), data()).unwrap();
+
+    assert_eq!(output, render(input.to_string(), data()).unwrap());
+}
+
+#[test]
+fn test_warning_none() {
+    let input = r#"
+    <div>
+        <p v-if="list.length">{{ user.name }}</p>
+        <p v-else>none</p>
+        <p v-for="item in list" :title="item">{{ item }}</p>
+    </div>
+    "#;
+
+    assert_eq!(warnings(input), vec![]);
+}
+
+// === Directives ===
+
+#[test]
+fn test_warning_orphan_else() {
+    let input = r#"
+    <div>
+        <p v-else>orphan</p>
+        <p v-if="false">if</p>
+        <p v-else>else</p>
+        <span>break</span>
+        <p v-else-if="true">orphan</p>
+    </div>
+    "#;
+
+    assert_eq!(
+        warnings(input),
+        vec![
+            (WarningKind::OrphanElse, Some(3)),
+            (WarningKind::OrphanElse, Some(7)),
+        ]
+    );
+}
+
+#[test]
+fn test_warning_if_with_for() {
+    let input = r#"
+    <ul>
+        <li v-for="item in list" v-if="list.length">{{ item }}</li>
+    </ul>
+    "#;
+
+    assert_eq!(warnings(input), vec![(WarningKind::IfWithFor, Some(3))]);
+}
+
+#[test]
+fn test_warning_invalid_bind_object() {
+    let input = r#"
+    <div>
+        <p v-bind="user.name">a</p>
+    </div>
+    "#;
+    let (output, warnings) = render_with_warnings(input.to_string(), data()).unwrap();
+
+    assert!(output.contains(r#"<p v-bind="user.name">a</p>"#));
+    assert_eq!(
+        warnings,
+        vec![Warning {
+            kind: WarningKind::InvalidBindObject,
+            file: None,
+            line: Some(3),
+            message: r#"`v-bind="user.name"` is not an object"#.to_string(),
+        }]
+    );
+}
+
+// === Expressions ===
+
+#[test]
+fn test_warning_eval_error() {
+    let input = r#"
+    <div>
+        <p>{{ user.name }}
+           {{ missing.name }}</p>
+        <p :title="other.title">b</p>
+    </div>
+    "#;
+    let (_, warnings) = render_with_warnings(input.to_string(), data()).unwrap();
+
+    let lines: Vec<_> = warnings.iter().map(|w| (w.kind, w.line)).collect();
+    assert_eq!(
+        lines,
+        vec![
+            (WarningKind::EvalError, Some(4)),
+            (WarningKind::EvalError, Some(5)),
+        ]
+    );
+    assert!(warnings[0].message.starts_with("`missing.name`: "));
+    assert!(warnings[1].message.starts_with("`other.title`: "));
+}
+
+#[test]
+fn test_warning_eval_error_once_per_line() {
+    // The same failure in every iteration of a loop is reported once
+    let input = r#"
+    <ul>
+        <li v-for="item in list">{{ item.missing.name }}</li>
+    </ul>
+    "#;
+
+    assert_eq!(warnings(input), vec![(WarningKind::EvalError, Some(3))]);
+}
+
+// === Parsing ===
+
+#[test]
+fn test_warning_duplicate_attribute() {
+    let input = r#"
+    <div>
+        <p class="a" class="b">a</p>
+    </div>
+    "#;
+    let (output, warnings) = render_with_warnings(input.to_string(), data()).unwrap();
+
+    assert!(output.contains(r#"<p class="a">a</p>"#));
+    let lines: Vec<_> = warnings.iter().map(|w| (w.kind, w.line)).collect();
+    assert_eq!(lines, vec![(WarningKind::DuplicateAttribute, Some(3))]);
+}
+
+// === Includes ===
+
+#[test]
+fn test_warning_include_file() {
+    // Warnings of an included template carry its name and lines
+    let loader = Loader::new().template(
+        "card.html",
+        "<div>\n  <p class=\"a\" class=\"b\">{{ missing.name }}</p>\n</div>",
+    );
+    let input = r#"
+    <main>
+        <include src="card.html"></include>
+        <p>{{ other.name }}</p>
+    </main>
+    "#;
+    let (_, warnings) = Options::new()
+        .loader(loader)
+        .render_with_warnings(input.to_string(), data())
+        .unwrap();
+
+    let files: Vec<_> = warnings
+        .iter()
+        .map(|w| (w.kind, w.file.as_deref(), w.line))
+        .collect();
+    assert_eq!(
+        files,
+        vec![
+            (WarningKind::DuplicateAttribute, Some("card.html"), Some(2)),
+            (WarningKind::EvalError, Some("card.html"), Some(2)),
+            (WarningKind::EvalError, None, Some(4)),
+        ]
+    );
+    assert!(warnings[1].to_string().ends_with(" at card.html:2"));
+}
+
+// === Messages ===
+
+#[test]
+fn test_warning_missing_message() {
+    let input = r#"
+    <div>
+        <p>{{ $t("known") }}</p>
+        <p>{{ $t("unknown") }} {{ $tc("plural", 2) }}</p>
+    </div>
+    "#;
+    let (output, warnings) = Options::new()
+        .messages("en", json!({ "known": "Known" }))
+        .render_with_warnings(input.to_string(), data())
+        .unwrap();
+
+    assert!(output.contains("<p>unknown plural</p>"));
+    assert_eq!(
+        warnings,
+        vec![
+            Warning {
+                kind: WarningKind::MissingMessage,
+                file: None,
+                line: Some(4),
+                message: "no message for `unknown`".to_string(),
+            },
+            Warning {
+                kind: WarningKind::MissingMessage,
+                file: None,
+                line: Some(4),
+                message: "no message for `plural`".to_string(),
+            },
+        ]
+    );
+}
@@ -0,0 +1,184 @@
+use prevue::{Options, Whitespace};
+use serde_json::{Value, json};
+
+fn data() -> Value {
+    json!({
+        "list": [1, 2, 3],
+    })
+}
+
+fn render(input: &str, whitespace: Whitespace) -> String {
+    Options::new()
+        .whitespace(whitespace)
+        .render(input.to_string(), data())
+        .unwrap()
+}
+
+fn render_stream(input: &str, whitespace: Whitespace) -> String {
+    Options::new()
+        .whitespace(whitespace)
+        .render_stream(input.to_string(), data())
+        .unwrap()
+        .collect::<Result<String, _>>()
+        .unwrap()
+}
+
+#[test]
+fn test_whitespace_condense() {
+    let input = r#"
+    <div>
+        <p>Hello,   world!</p>
+        <!-- note -->
+        <span>a</span> <span>b</span>
+        <ul>
+            <li v-for="item in list">{{ item }}</li>
+        </ul>
+    </div>
+    "#;
+    let output = render(input, Whitespace::Condense);
+
+    let expected = "<html><head></head><body><div><p>Hello, world!</p><!-- note --><span>a</span> <span>b</span><ul><li>1</li><li>2</li><li>3</li></ul></div></body></html>";
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_whitespace_condense_keeps_pre() {
+    let input = "<pre>  a\n    b  </pre><textarea>  x  </textarea>";
+    let output = render(input, Whitespace::Condense);
+
+    let expected =
+        "<html><head></head><body><pre>  a\n    b  </pre><textarea>  x  </textarea></body></html>";
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_whitespace_minify() {
+    let input = r#"
+    <div class="box" id="main" title="two words" data-empty="">
+        <!-- removed -->
+        <input type="checkbox" checked>
+        <span>a</span> <span>b</span>
+    </div>
+    "#;
+    let output = render(input, Whitespace::Minify);
+
+    let expected = r#"<html><head></head><body><div class=box id=main title="two words" data-empty><input type=checkbox checked><span>a</span> <span>b</span></div></body></html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_whitespace_pretty() {
+    let input = r#"<div><h1>Title</h1><ul><li v-for="item in list">{{ item }}</li></ul><p>Some <b>bold</b>   text</p></div>"#;
+    let output = render(input, Whitespace::Pretty);
+
+    let expected = r#"<html>
+  <head></head>
+  <body>
+    <div>
+      <h1>Title</h1>
+      <ul>
+        <li>1</li>
+        <li>2</li>
+        <li>3</li>
+      </ul>
+      <p>Some <b>bold</b> text</p>
+    </div>
+  </body>
+</html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_whitespace_pretty_reindents() {
+    let input = r#"
+    <div>
+              <p>a</p>
+      <p>b</p>
+    </div>
+    "#;
+    let output = render(input, Whitespace::Pretty);
+
+    let expected = r#"<html>
+  <head></head>
+  <body>
+    <div>
+      <p>a</p>
+      <p>b</p>
+    </div>
+  </body>
+</html>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_whitespace_fragment() {
+    // Fragments are formatted as top-level nodes
+    let input = r#"
+    <div>
+              <p>a</p>
+      <p v-for="n in list">{{ n }}</p>
+    </div>
+    <p>b</p>
+    "#;
+    let output = Options::new()
+        .fragment(true)
+        .whitespace(Whitespace::Pretty)
+        .render(input.to_string(), data())
+        .unwrap();
+
+    let expected = r#"<div>
+  <p>a</p>
+  <p>1</p>
+  <p>2</p>
+  <p>3</p>
+</div>
+<p>b</p>"#;
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn test_whitespace_fragment_head_elements() {
+    // The parser moves leading `<script>`, `<style>` and `<template>` to `<head>`
+    let cases = [
+        (
+            r#"<template v-for="n in list"><b>{{ n }}</b></template><p>after</p>"#,
+            "<b>1</b><b>2</b><b>3</b><p>after</p>",
+        ),
+        (
+            "<script>a()</script><p>after</p>",
+            "<script>a()</script><p>after</p>",
+        ),
+        (
+            "<style>p { color: red }</style><p>after</p>",
+            "<style>p { color: red }</style><p>after</p>",
+        ),
+    ];
+    for (input, expected) in cases {
+        let output = Options::new()
+            .fragment(true)
+            .render(input.to_string(), data())
+            .unwrap();
+        assert_eq!(output, expected);
+    }
+}
+
+#[test]
+fn test_whitespace_stream() {
+    let input = r#"
+    <div>
+        <p>Hello,   world!</p>
+        <ul>
+            <li v-for="item in list">{{ item }}</li>
+        </ul>
+    </div>
+    "#;
+
+    for whitespace in [
+        Whitespace::Preserve,
+        Whitespace::Condense,
+        Whitespace::Minify,
+        Whitespace::Pretty,
+    ] {
+        assert_eq!(render_stream(input, whitespace), render(input, whitespace));
+    }
+}